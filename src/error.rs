@@ -28,6 +28,26 @@ pub enum RouterError {
         destination: NodeId,
         error: crossbeam_channel::SendError<Packet>,
     },
+    InvalidPathTrace(PathTraceError),
+}
+
+/// Why a path trace cannot be learned, see `PathTraceValidator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathTraceError {
+    Empty,
+    /// The trace does not start at the router
+    NotFromRoot {
+        first: NodeId,
+    },
+    /// The same node twice in a row
+    SelfLoop(NodeId),
+    /// A node already crossed by the trace
+    Repeated(NodeId),
+    /// A client or a server forwarded the flood
+    LeafForwarded {
+        id: NodeId,
+        node_type: NodeType,
+    },
 }
 
 impl std::fmt::Display for RouterError {
@@ -0,0 +1,97 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use crate::error::PathTraceError;
+use std::collections::VecDeque;
+use wg_2024::packet::FloodResponse;
+
+/// Number of rejected flood responses kept, see `Router::quarantined_flood_responses`
+pub const FLOOD_QUARANTINE_SIZE: usize = 16;
+
+/// Why a flood response was not trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloodRejection {
+    /// The flood id was never issued by this router
+    UnknownFlood,
+    /// The path trace does not start at this router
+    WrongInitiator,
+    /// The flood round already ended or has been forgotten
+    Stale,
+    /// The path trace is malformed, see `PathTraceValidator`
+    InvalidPathTrace(PathTraceError),
+}
+
+/// Flood responses received by the router, see `Router::flood_response_stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FloodResponseStats {
+    /// Responses learned in the topology, the ones admitted for a rolled back round
+    /// are not learned and not counted
+    pub accepted: u64,
+    pub unknown_flood: u64,
+    pub wrong_initiator: u64,
+    pub invalid_path_trace: u64,
+    /// Responses of flood rounds already ended or forgotten, learned anyway
+    /// unless in strict mode
+    pub stale: u64,
+}
+
+/// Counts the flood responses and keeps the last rejected ones
+#[derive(Debug, Default)]
+pub struct FloodGuard {
+    /// Stale responses are rejected too
+    strict: bool,
+    stats: FloodResponseStats,
+    quarantine: VecDeque<(FloodResponse, FloodRejection)>,
+}
+
+impl FloodGuard {
+    //constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FloodGuard {
+    //methods
+    /// Counts `response` if it has to be rejected, and quarantines it
+    /// # Returns
+    /// `true` if the response can update the topology, see `learned`
+    pub fn admit(&mut self, response: &FloodResponse, rejection: Option<FloodRejection>) -> bool {
+        let Some(rejection) = rejection else {
+            return true;
+        };
+        match rejection {
+            FloodRejection::UnknownFlood => self.stats.unknown_flood += 1,
+            FloodRejection::WrongInitiator => self.stats.wrong_initiator += 1,
+            FloodRejection::InvalidPathTrace(_) => self.stats.invalid_path_trace += 1,
+            FloodRejection::Stale => {
+                self.stats.stale += 1;
+                if !self.strict {
+                    return true;
+                }
+            }
+        }
+        if self.quarantine.len() == FLOOD_QUARANTINE_SIZE {
+            self.quarantine.pop_front();
+        }
+        self.quarantine.push_back((response.clone(), rejection));
+        false
+    }
+    /// Counts an admitted response that updated the topology
+    pub fn learned(&mut self) {
+        self.stats.accepted += 1;
+    }
+}
+
+impl FloodGuard {
+    //getter/setter
+    pub fn stats(&self) -> FloodResponseStats {
+        self.stats
+    }
+    /// From the oldest
+    pub fn quarantined(&self) -> Vec<(FloodResponse, FloodRejection)> {
+        self.quarantine.iter().cloned().collect()
+    }
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+}
@@ -1,9 +1,10 @@
 	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
 
+use super::FloodRejection;
 use std::cell::RefCell;
 use wg_2024::{
     network::{NodeId, SourceRoutingHeader},
-    packet::{FloodRequest, NodeType, Packet, PacketType},
+    packet::{FloodRequest, FloodResponse, NodeType, Packet, PacketType},
 };
 
 // use neighbour::NeighBour;
@@ -55,6 +56,28 @@ impl FloodRequestFactory {
             .map(|_| self.flood_request_to_packet(self.create_request()))
             .collect()
     }
+    /// Checks that `response` answers a flood request made by this factory
+    pub fn check_response(&self, response: &FloodResponse) -> Option<FloodRejection> {
+        if response.path_trace.first().map(|(id, _)| *id) != Some(self.id) {
+            Some(FloodRejection::WrongInitiator)
+        } else if self
+            .flood_ids
+            .borrow()
+            .binary_search(&response.flood_id)
+            .is_err()
+        {
+            Some(FloodRejection::UnknownFlood)
+        } else {
+            None
+        }
+    }
+    /// Flood id of the next request
+    pub fn next_flood_id(&self) -> u64 {
+        self.flood_ids
+            .borrow()
+            .last()
+            .map_or_else(|| 0, |id| id + 1)
+    }
     /// send a `flood request` only to `id`
     /// # Errors
     /// - `IdNotFound` if the `id` is not in the neighbours
@@ -95,11 +118,7 @@ impl FloodRequestFactory {
     //     self.flood_send.contains_key(&id)
     // }
     fn create_request(&self) -> FloodRequest {
-        let flood_id = self
-            .flood_ids
-            .borrow()
-            .last()
-            .map_or_else(|| 0, |id| id + 1);
+        let flood_id = self.next_flood_id();
 
         self.flood_ids.borrow_mut().push(flood_id);
         let mut a = FloodRequest::new(flood_id, self.id);
@@ -1,24 +1,73 @@
 	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
 
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::error::Result;
+use flood_guard::FloodGuard;
 use flood_requester::FloodRequestFactory;
 use log::info;
 use network_holder::NetworkHolder;
+use path_selector::PathSelector;
 use wg_2024::{
     network::{NodeId, SourceRoutingHeader},
-    packet::{FloodResponse, NodeType, Packet},
+    packet::{FloodResponse, NackType, NodeType, Packet, PacketType},
 };
 
+mod flood_guard;
 mod flood_requester;
 mod network_holder;
+mod path_selector;
+#[cfg(test)]
+mod test;
+
+pub use flood_guard::{FloodRejection, FloodResponseStats, FLOOD_QUARANTINE_SIZE};
+pub use network_holder::{
+    ArticulationPoint, Bridge, Clock, Confidence, ConvergenceRule, DeliveryProbability,
+    DiscoveryState, DiscoveryStatus, Disjointness, DropCount, FloodRound, FloodRoundState,
+    GenerationInfo, HopCount, LinkConfidence, LinkExpiry, LinkStats, ManualClock, Partition,
+    PathTraceValidator, PdrEstimate, RouteCacheStats, RouteConstraints, RouteMetric, RoutingTable,
+    SystemClock, TopologyAnalysis, TypeConflict, WeightDecay, WeightedMetric,
+    DECAY_REFRESHES_PER_HALF_LIFE, FLOOD_ROUND_HISTORY, FLOOD_ROUND_TIMEOUT, PDR_EWMA_ALPHA,
+    TOMBSTONE_LIFETIME, TOPOLOGY_GENERATIONS, TYPE_CONFLICT_HISTORY,
+};
+pub use path_selector::{
+    Outcome, SelectionStrategy, PATH_SELECTOR_CANDIDATES, PATH_SELECTOR_COST_RATIO,
+};
+
+/// Maximum number of paths returned by `get_multiple_source_routing_headers`,
+/// which returned every loop-free path before it used Yen's algorithm
+pub const MULTIPLE_PATHS_LIMIT: usize = 8;
+
+/// Result of `Router::get_disjoint_source_routing_headers`
+#[derive(Debug, Clone)]
+pub struct DisjointRoutes {
+    pub headers: Vec<SourceRoutingHeader>,
+    pub requested: usize,
+}
+
+impl DisjointRoutes {
+    /// Number of disjoint routes found, can be less than `requested`
+    #[must_use]
+    pub fn found(&self) -> usize {
+        self.headers.len()
+    }
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.found() >= self.requested
+    }
+}
 
 #[derive(Debug)]
 pub struct Router {
     id: NodeId,
     network_holder: NetworkHolder,
     requester: FloodRequestFactory,
+    flood_guard: FloodGuard,
+    path_selector: PathSelector,
 }
 
 impl Router {
@@ -29,17 +78,128 @@ impl Router {
             id,
             network_holder: NetworkHolder::new(id, node_type),
             requester: FloodRequestFactory::new(id, node_type),
+            flood_guard: FloodGuard::new(),
+            path_selector: PathSelector::new(SelectionStrategy::default()),
+        }
+    }
+    /// Router that computes the routes with `metric` instead of the default `DropCount`
+    #[must_use]
+    pub fn with_metric(
+        id: NodeId,
+        node_type: NodeType,
+        metric: impl RouteMetric + 'static,
+    ) -> Self {
+        Self {
+            id,
+            network_holder: NetworkHolder::with_metric(id, node_type, Arc::new(metric)),
+            requester: FloodRequestFactory::new(id, node_type),
+            flood_guard: FloodGuard::new(),
+            path_selector: PathSelector::new(SelectionStrategy::default()),
         }
     }
 }
 
 impl Router {
     //methods
+    /// Learns the topology from `resp` if it answers a flood requested by this router,
+    /// otherwise it is counted and quarantined (see `flood_response_stats`).
+    /// Responses of flood rounds already ended (see `flood_round`) are still learned,
+    /// counted as stale, unless in strict mode
     pub fn handle_flood_response(&mut self, resp: &FloodResponse) {
-        self.network_holder.received_flood_response(resp);
+        let rejection = self
+            .requester
+            .check_response(resp)
+            .or_else(|| {
+                self.network_holder
+                    .validate_path_trace(&resp.path_trace)
+                    .err()
+                    .map(FloodRejection::InvalidPathTrace)
+            })
+            .or_else(|| {
+                self.network_holder
+                    .is_stale_flood(resp.flood_id)
+                    .then_some(FloodRejection::Stale)
+            });
+        if self.flood_guard.admit(resp, rejection) {
+            if self.network_holder.received_flood_response(resp) {
+                self.flood_guard.learned();
+            }
+        } else {
+            info!(
+                "[RouterOf: {}] rejected flood response {}: {rejection:?}",
+                self.id, resp.flood_id
+            );
+        }
+    }
+    /// Learns the topology from a `Nack`, the node reporting it being the first hop of
+    /// its routing header:
+    /// - `ErrorInRouting(next)`: the link between the reporter and `next` is removed
+    ///   (and buried, see `set_tombstone_lifetime`)
+    /// - `DestinationIsDrone`: the reporter is a drone, the responses to the floods
+    ///   already requested do not change it back
+    /// - `UnexpectedRecipient`: the routes following the nacked packet's path somewhere
+    ///   else than the reporter are not used by `next_source_routing_header` until
+    ///   the topology changes
+    /// - `Dropped`: the drop is charged to the reporter, as `dropped_fragment`
+    ///   and `packet_dropped_at` do
+    ///
+    /// Other packets are ignored
+    pub fn handle_nack(&mut self, packet: &Packet) {
+        let PacketType::Nack(nack) = &packet.pack_type else {
+            return;
+        };
+        let Some(reporter) = packet.routing_header.hops.first().copied() else {
+            return;
+        };
+        info!(
+            "[RouterOf: {}] nack {:?} from {reporter}",
+            self.id, nack.nack_type
+        );
+        match nack.nack_type {
+            NackType::ErrorInRouting(next) => self.network_holder.remove_link(reporter, next),
+            NackType::DestinationIsDrone => {
+                self.network_holder.set_node_type(reporter, NodeType::Drone);
+            }
+            NackType::UnexpectedRecipient(_) => {
+                // the nack goes back along the path of the packet,
+                // which was sent to another node than the reporter
+                let prefix = packet.routing_header.hops[1..]
+                    .iter()
+                    .rev()
+                    .copied()
+                    .collect();
+                self.path_selector
+                    .set_structure_epoch(self.network_holder.structure_epoch());
+                self.path_selector.mark_stale(prefix, reporter);
+            }
+            NackType::Dropped => {
+                self.network_holder.dropped_fragment(reporter);
+                self.network_holder.packet_dropped_at(reporter);
+            }
+        }
+    }
+    /// Flood responses accepted and rejected
+    pub fn flood_response_stats(&self) -> FloodResponseStats {
+        self.flood_guard.stats()
+    }
+    /// The last `FLOOD_QUARANTINE_SIZE` rejected flood responses, from the oldest
+    pub fn quarantined_flood_responses(&self) -> Vec<(FloodResponse, FloodRejection)> {
+        self.flood_guard.quarantined()
+    }
+    /// In strict mode the responses of flood rounds already ended (see `flood_round`)
+    /// are rejected too, they change neither the topology nor their round
+    pub fn set_strict_flood_responses(&mut self, strict: bool) {
+        self.flood_guard.set_strict(strict);
+    }
+    /// By default malformed path traces are repaired (see `PathTraceValidator`),
+    /// when `repair` is false the flood responses carrying them are rejected.
+    /// The traces that do not start at this router are always rejected
+    pub fn set_path_trace_repair(&mut self, repair: bool) {
+        self.network_holder.set_path_trace_repair(repair);
     }
     /// # Errors
     /// - `Err(RouteNotFound)` if the destionation is unreachable
+    /// - `Err(ParentsMalformed)` if the shortest path tree is malformed
     pub fn get_source_routing_header(&self, destination: NodeId) -> Result<SourceRoutingHeader> {
         let path: Vec<NodeId> = self.network_holder.get_path(destination)?;
         let header = SourceRoutingHeader::with_first_hop(path).without_loops();
@@ -47,46 +207,295 @@ impl Router {
         info!("[RouterOf: {}] header: {header}", self.id);
         Ok(header)
     }
+    /// Like `get_source_routing_header`, but the route avoids `constraints.avoid`,
+    /// crosses `constraints.waypoints` in order and has at most `constraints.max_hops` links
+    /// # Errors
+    /// - `Err(RouteNotFound)` if no route satisfies the constraints
+    /// - `Err(IdNotFound)` if a waypoint is unknown
+    pub fn get_source_routing_header_with(
+        &self,
+        destination: NodeId,
+        constraints: &RouteConstraints,
+    ) -> Result<SourceRoutingHeader> {
+        let path = self
+            .network_holder
+            .get_constrained_path(destination, constraints)?;
+        let header = SourceRoutingHeader::with_first_hop(path);
+        info!("[RouterOf: {}] constrained header: {header}", self.id);
+        Ok(header)
+    }
+    /// Like `get_source_routing_header`, but spreads the packets to `destination` over
+    /// up to `PATH_SELECTOR_CANDIDATES` good routes according to the `SelectionStrategy`.
+    /// The routes are recomputed when the topology changes
+    /// # Errors
+    /// - `Err(RouteNotFound)` if the destionation is unreachable
+    /// - `Err(ParentsMalformed)` if the shortest path tree is malformed
+    pub fn next_source_routing_header(
+        &mut self,
+        destination: NodeId,
+    ) -> Result<SourceRoutingHeader> {
+        let epoch = self.network_holder.topology_epoch();
+        self.path_selector
+            .set_structure_epoch(self.network_holder.structure_epoch());
+        let network_holder = &self.network_holder;
+        let path = self.path_selector.select(epoch, destination, || {
+            network_holder.get_candidate_paths(destination, PATH_SELECTOR_CANDIDATES)
+        })?;
+        Ok(SourceRoutingHeader::with_first_hop(path))
+    }
+    /// Feedback for `SelectionStrategy::Ucb`: `Outcome::Acked` when the packet sent with
+    /// `header` has been acked, `Outcome::Nacked` when it has been nacked.
+    /// Only the routes returned by `next_source_routing_header` are tracked, and forgotten
+    /// once they are no longer computed.
+    /// Call `packet_acked`/`packet_dropped_at` as well to update the pdr estimates
+    pub fn report_outcome(&mut self, header: &SourceRoutingHeader, outcome: Outcome) {
+        self.path_selector.report(&header.hops, outcome);
+    }
+    /// Changes how `next_source_routing_header` chooses the routes,
+    /// the default is `SelectionStrategy::WeightedRoundRobin`
+    pub fn set_selection_strategy(&mut self, strategy: SelectionStrategy) {
+        self.path_selector.set_strategy(strategy);
+    }
+    /// Routes to every known destination with their costs,
+    /// computed once per topology change
+    /// # Errors
+    /// - `Err(IdNotFound)` if the network is inconsistent
+    pub fn routing_table(&self) -> Result<RoutingTable> {
+        Ok(self.network_holder.routing_table()?.as_ref().clone())
+    }
+    /// Increases every time the known topology or the weights change,
+    /// also when the weights decay with time
+    pub fn topology_epoch(&self) -> u64 {
+        self.network_holder.topology_epoch()
+    }
+    /// Hits and misses of the cache used by `get_source_routing_header`
+    pub fn route_cache_stats(&self) -> RouteCacheStats {
+        self.network_holder.route_cache_stats()
+    }
+    /// Drones (articulation points) and links (bridges) whose failure alone would make
+    /// part of the known network unreachable, with the nodes each one would cut off
+    pub fn topology_analysis(&self) -> TopologyAnalysis {
+        self.network_holder.topology_analysis()
+    }
     pub fn log_network(&self) {
         // self.network.log_network();
     }
 
+    /// Returns at most `MULTIPLE_PATHS_LIMIT` paths, the cheapest first.
+    /// Any other path is left out, use `get_k_best_source_routing_headers`
+    /// to choose how many are returned
     pub fn get_multiple_source_routing_headers(
         &self,
         destination: NodeId,
     ) -> Vec<SourceRoutingHeader> {
-        let paths = self.network_holder.get_multiple_paths(destination);
+        let paths = self
+            .network_holder
+            .get_multiple_paths(destination, MULTIPLE_PATHS_LIMIT);
         let mut source_routing_headers = Vec::new();
         for path in paths {
             source_routing_headers.push(SourceRoutingHeader::initialize(path));
         }
         source_routing_headers
     }
+    /// Returns the `k` cheapest loop-free headers to `destination`, sorted by cost
+    /// # Errors
+    /// - `Err(RouteNotFound)` if the destionation is unreachable
+    /// - `Err(ParentsMalformed)` if the shortest path tree is malformed
+    pub fn get_k_best_source_routing_headers(
+        &self,
+        destination: NodeId,
+        k: usize,
+    ) -> Result<Vec<SourceRoutingHeader>> {
+        let paths = self.network_holder.get_k_best_paths(destination, k)?;
+        Ok(paths
+            .into_iter()
+            .map(SourceRoutingHeader::with_first_hop)
+            .collect())
+    }
+    /// Returns up to `count` headers to `destination` that do not share intermediate drones
+    /// (`Disjointness::Node`) or links (`Disjointness::Link`), so that a single
+    /// crashed or lossy drone can not take out every copy of a packet.
+    /// Check `DisjointRoutes::found` to know how many were found.
+    pub fn get_disjoint_source_routing_headers(
+        &self,
+        destination: NodeId,
+        count: usize,
+        disjointness: Disjointness,
+    ) -> DisjointRoutes {
+        let headers = self
+            .network_holder
+            .get_disjoint_paths(destination, count, disjointness)
+            .into_iter()
+            .map(SourceRoutingHeader::with_first_hop)
+            .collect();
+        DisjointRoutes {
+            headers,
+            requested: count,
+        }
+    }
     /// # Returns:
     /// A Vec<Packet> with the size specified in `count`
+    /// A new topology generation is started for the responses to these requests
     pub fn get_flood_requests(&mut self, count: usize) -> Vec<Packet> {
-        self.network_holder.asked_flood_request();
-        self.requester.get_flood_request(count)
+        let first = self.requester.next_flood_id();
+        let requests = self.requester.get_flood_request(count);
+        self.network_holder
+            .asked_flood_request(first..self.requester.next_flood_id());
+        requests
+    }
+    /// Responses received for the flood `flood_id` and what they taught,
+    /// `None` if the flood was not requested by this router or is too old
+    pub fn flood_round(&self, flood_id: u64) -> Option<FloodRound> {
+        self.network_holder.flood_round(flood_id)
+    }
+    /// The last flood rounds requested by this router, ordered by flood id
+    pub fn flood_rounds(&self) -> Vec<FloodRound> {
+        self.network_holder.flood_rounds()
+    }
+    /// `Converged` once the last flood requests stop teaching new nodes or links
+    /// (see `ConvergenceRule`), when it is safe to start sending.
+    /// The coverage numbers tell how much of the network the last flood requests
+    /// have taught, which can be less than the network used for routing
+    pub fn discovery_state(&self) -> DiscoveryState {
+        self.network_holder.discovery_state()
+    }
+    /// Changes when `discovery_state` reports a discovery as converged or stale
+    pub fn set_convergence_rule(&mut self, rule: ConvergenceRule) {
+        self.network_holder.set_convergence_rule(rule);
+    }
+    /// Time without responses after which a flood round ends, `FLOOD_ROUND_TIMEOUT` by default
+    pub fn set_flood_round_timeout(&mut self, timeout: Duration) {
+        self.network_holder.set_flood_round_timeout(timeout);
+    }
+    /// Forgets the topology learned in the last flood round, for when it turned out
+    /// to be partial: routing goes back to the previous generation.
+    /// `None` if there is only one generation
+    pub fn rollback_generation(&mut self) -> Option<GenerationInfo> {
+        self.network_holder.rollback_generation()
+    }
+    /// The topology generations kept, from the oldest to the newest
+    pub fn generations(&self) -> Vec<GenerationInfo> {
+        self.network_holder.generations()
+    }
+    /// Number of flood rounds whose topology is kept, `TOPOLOGY_GENERATIONS` by default
+    pub fn set_generation_limit(&mut self, limit: usize) {
+        self.network_holder.set_generation_limit(limit);
     }
+    /// The drone is forgotten, and the late responses to the floods requested before
+    /// the crash cannot add it back (see `set_tombstone_lifetime`)
     pub fn drone_crashed(&mut self, id: NodeId) {
         self.network_holder.drone_crashed(id);
     }
+    /// How long a crashed drone or a removed neighbour link is kept from being learned
+    /// again from old flood responses, `TOMBSTONE_LIFETIME` by default
+    pub fn set_tombstone_lifetime(&mut self, lifetime: Duration) {
+        self.network_holder.set_tombstone_lifetime(lifetime);
+    }
+    /// Charge a drop to the drone `id1` (the one named by a `Nack` of type `Dropped`):
+    /// every route crossing it pays the drop once
     pub fn dropped_fragment(&mut self, id1: NodeId) {
         self.network_holder.dropped_fragment(id1);
     }
+    /// Charge a drop only to the link between `id1` and `next`,
+    /// when it is known where `id1` was forwarding the packet
+    pub fn dropped_fragment_on_link(&mut self, id1: NodeId, next: NodeId) {
+        self.network_holder.dropped_fragment_on_link(id1, next);
+    }
+    /// Changes how fast the drops counted by `dropped_fragment` are forgotten,
+    /// the default halves them every minute (the routes follow the decayed weights
+    /// `DECAY_REFRESHES_PER_HALF_LIFE` times per minute)
+    pub fn set_weight_decay(&mut self, decay: WeightDecay) {
+        self.network_holder.set_weight_decay(decay);
+    }
+    /// Replaces the real time, used for the timestamps of the topology and the decay
+    /// of the weights (see `ManualClock`). To be called before learning the topology
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.network_holder.set_clock(clock);
+    }
+    /// Changes what happens to the links that no flood response confirmed recently,
+    /// by default they never expire
+    pub fn set_link_expiry(&mut self, expiry: LinkExpiry) {
+        self.network_holder.set_link_expiry(expiry);
+    }
+    /// Applies the `LinkExpiry`, it is done also at every flood response and flood request
+    pub fn expire_stale_topology(&mut self) {
+        self.network_holder.expire_links();
+    }
+    /// Last time the node `id` was confirmed by a flood response
+    pub fn last_confirmed(&self, id: NodeId) -> Option<Instant> {
+        self.network_holder.last_confirmed(id)
+    }
+    /// Last time the link between `id1` and `id2` was confirmed by a flood response
+    pub fn link_last_confirmed(&self, id1: NodeId, id2: NodeId) -> Option<Instant> {
+        self.network_holder.link_last_confirmed(id1, id2)
+    }
+    /// How many flood responses and floods confirmed the link, `None` if the link is unknown
+    pub fn link_confidence(&self, id1: NodeId, id2: NodeId) -> Option<LinkConfidence> {
+        self.network_holder.link_confidence(id1, id2)
+    }
+    /// A neighbour not known yet is guessed to be a drone,
+    /// its type is corrected by the first path trace crossing it
     pub fn add_neighbour(&mut self, id: NodeId) {
         self.network_holder.add_neighbour(id);
     }
+    /// `None` if `id` is unknown
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        self.network_holder.node_type(id)
+    }
+    /// The last nodes whose type was corrected by a path trace (the type in the trace wins),
+    /// from the oldest
+    pub fn type_conflicts(&self) -> Vec<TypeConflict> {
+        self.network_holder.type_conflicts()
+    }
+    /// To be called for every packet sent with `header`,
+    /// the drones in the header are counted as exposed to the packet
+    pub fn packet_sent(&mut self, header: &SourceRoutingHeader) {
+        self.network_holder.packet_sent(&header.hops);
+    }
+    /// To be called when the packet sent with `header` has been acked,
+    /// every drone in the header forwarded it
+    pub fn packet_acked(&mut self, header: &SourceRoutingHeader) {
+        self.network_holder.packet_acked(&header.hops);
+    }
+    /// To be called when a `Nack` of type `Dropped` is received from `id`
+    pub fn packet_dropped_at(&mut self, id: NodeId) {
+        self.network_holder.packet_dropped_at(id);
+    }
+    /// Estimated packet drop rate of the drone `id` with its confidence bounds,
+    /// `None` if no packet has been routed through it yet
+    pub fn estimated_pdr(&self, id: NodeId) -> Option<PdrEstimate> {
+        self.network_holder.estimated_pdr(id)
+    }
 
     pub fn remove_neighbour(&mut self, id: NodeId) {
         self.network_holder.remove_neighbour(id);
     }
 
     /// Returns the list of server in the network, used to determine which server is Chat
-    /// and which is Media/Text  
+    /// and which is Media/Text.
+    /// Servers cut off by crashes or removed neighbours are not listed,
+    /// see `get_unreachable_server_list`
     pub fn get_server_list(&self) -> HashSet<NodeId> {
         self.network_holder.get_server_list()
     }
+    /// Known servers that are currently unreachable
+    pub fn get_unreachable_server_list(&self) -> HashSet<NodeId> {
+        self.network_holder.get_unreachable_server_list()
+    }
+    /// Nodes reachable from this router and the components cut off from it
+    pub fn partition(&self) -> Partition {
+        self.network_holder.partition()
+    }
+    /// When `prune` is true the nodes cut off by a crash or a removed neighbour are
+    /// forgotten, they will be learned again from the next flood responses
+    pub fn set_prune_orphans(&mut self, prune: bool) {
+        self.network_holder.set_prune_orphans(prune);
+    }
+    /// `callback` is called with the partition of the network every time
+    /// `drone_crashed` or `remove_neighbour` change the part of it that is cut off
+    pub fn on_partition(&mut self, callback: impl FnMut(&Partition) + Send + 'static) {
+        self.network_holder.on_partition(callback);
+    }
     // pub fn clear_routing_table(&mut self) {
     //     self.primary_network = Network::new(self.id, self.node_type);
     // }
@@ -0,0 +1,147 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+/// When a discovery is considered converged or stale, see `Router::set_convergence_rule`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConvergenceRule {
+    /// Converged after this many consecutive responses that taught nothing
+    pub quiet_responses: usize,
+    /// Converged when nothing new has been learned for this long
+    pub quiet_time: Duration,
+    /// Stale when the last flood request is older than this
+    pub stale_after: Duration,
+}
+
+impl Default for ConvergenceRule {
+    fn default() -> Self {
+        Self {
+            quiet_responses: 5,
+            quiet_time: Duration::from_secs(1),
+            stale_after: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryStatus {
+    /// The last flood requests are still teaching new nodes or links
+    Discovering,
+    /// The last flood requests stopped teaching anything, the topology can be used
+    Converged,
+    /// No flood has been requested recently, or ever
+    Stale,
+}
+
+/// Progress of the last discovery, see `Router::discovery_state`.
+/// The coverage is the one of the generation the last flood requests are learned into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscoveryState {
+    pub status: DiscoveryStatus,
+    /// Nodes learned from the last flood requests, the router included
+    pub nodes: usize,
+    /// Links learned from the last flood requests
+    pub links: usize,
+    /// Servers reachable through what the last flood requests taught
+    pub servers: usize,
+    /// Responses to the last flood requests
+    pub responses: usize,
+    /// Consecutive responses that taught nothing
+    pub quiet_responses: usize,
+    /// Time since the last response that taught something,
+    /// or since the flood requests if none did
+    pub quiet_time: Option<Duration>,
+}
+
+/// Responses to the last batch of flood requests
+#[derive(Debug)]
+pub struct Discovery {
+    flood_ids: Range<u64>,
+    started: Option<Instant>,
+    responses: usize,
+    quiet_responses: usize,
+    last_change: Option<Instant>,
+    rule: ConvergenceRule,
+}
+
+impl Discovery {
+    //constructor
+    pub fn new() -> Self {
+        Self {
+            flood_ids: 0..0,
+            started: None,
+            responses: 0,
+            quiet_responses: 0,
+            last_change: None,
+            rule: ConvergenceRule::default(),
+        }
+    }
+}
+
+impl Discovery {
+    //methods
+    pub fn start(&mut self, flood_ids: Range<u64>, now: Instant) {
+        self.flood_ids = flood_ids;
+        self.started = Some(now);
+        self.responses = 0;
+        self.quiet_responses = 0;
+        self.last_change = None;
+    }
+    /// Counts a response, the ones of older floods are ignored
+    pub fn received(&mut self, flood_id: u64, now: Instant, learned: bool) {
+        if !self.flood_ids.contains(&flood_id) {
+            return;
+        }
+        self.responses += 1;
+        if learned {
+            self.quiet_responses = 0;
+            self.last_change = Some(now);
+        } else {
+            self.quiet_responses += 1;
+        }
+    }
+    /// The coverage numbers are those of the network used for routing
+    pub fn state(
+        &self,
+        now: Instant,
+        nodes: usize,
+        links: usize,
+        servers: usize,
+    ) -> DiscoveryState {
+        let quiet_time = self
+            .last_change
+            .or(self.started)
+            .map(|last| now.saturating_duration_since(last));
+        let status = match self.started {
+            Some(started) if now.saturating_duration_since(started) < self.rule.stale_after => {
+                let quiet = self.quiet_responses >= self.rule.quiet_responses
+                    || quiet_time.is_some_and(|time| time >= self.rule.quiet_time);
+                if self.responses > 0 && quiet {
+                    DiscoveryStatus::Converged
+                } else {
+                    DiscoveryStatus::Discovering
+                }
+            }
+            _ => DiscoveryStatus::Stale,
+        };
+        DiscoveryState {
+            status,
+            nodes,
+            links,
+            servers,
+            responses: self.responses,
+            quiet_responses: self.quiet_responses,
+            quiet_time,
+        }
+    }
+}
+
+impl Discovery {
+    //getter/setter
+    pub fn set_rule(&mut self, rule: ConvergenceRule) {
+        self.rule = rule;
+    }
+}
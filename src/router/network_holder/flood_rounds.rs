@@ -0,0 +1,180 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::{
+    collections::BTreeMap,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+/// A flood round ends when no response has arrived for this long,
+/// see `Router::set_flood_round_timeout`
+pub const FLOOD_ROUND_TIMEOUT: Duration = Duration::from_secs(2);
+/// Number of finished flood rounds remembered
+pub const FLOOD_ROUND_HISTORY: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloodRoundState {
+    /// Responses can still arrive
+    Pending,
+    /// Responses arrived, and then none for `FLOOD_ROUND_TIMEOUT`
+    Completed,
+    /// No response arrived within `FLOOD_ROUND_TIMEOUT`,
+    /// a late response completes it
+    TimedOut,
+}
+
+/// What a flood request issued by the router has discovered so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloodRound {
+    pub flood_id: u64,
+    pub started: Instant,
+    /// Arrival of the last response
+    pub last_response: Option<Instant>,
+    pub responses: usize,
+    /// Nodes added to the topology by the responses
+    pub new_nodes: usize,
+    /// Links added to the topology by the responses
+    pub new_links: usize,
+    pub state: FloodRoundState,
+}
+
+impl FloodRound {
+    //constructor
+    fn new(flood_id: u64, started: Instant) -> Self {
+        Self {
+            flood_id,
+            started,
+            last_response: None,
+            responses: 0,
+            new_nodes: 0,
+            new_links: 0,
+            state: FloodRoundState::Pending,
+        }
+    }
+}
+
+impl FloodRound {
+    //methods
+    /// Ends the round if nothing happened for `timeout`
+    fn refresh(&mut self, now: Instant, timeout: Duration) {
+        if self.state != FloodRoundState::Pending {
+            return;
+        }
+        let last_activity = self.last_response.unwrap_or(self.started);
+        if now.saturating_duration_since(last_activity) >= timeout {
+            self.state = if self.responses > 0 {
+                FloodRoundState::Completed
+            } else {
+                FloodRoundState::TimedOut
+            };
+        }
+    }
+}
+
+/// The flood rounds issued by the router, by flood id
+#[derive(Debug)]
+pub struct FloodRounds {
+    rounds: BTreeMap<u64, FloodRound>,
+    timeout: Duration,
+}
+
+impl FloodRounds {
+    //constructor
+    pub fn new() -> Self {
+        Self {
+            rounds: BTreeMap::new(),
+            timeout: FLOOD_ROUND_TIMEOUT,
+        }
+    }
+}
+
+impl FloodRounds {
+    //methods
+    pub fn start(&mut self, flood_id: u64, now: Instant) {
+        self.refresh(now);
+        self.rounds.insert(flood_id, FloodRound::new(flood_id, now));
+        self.forget_finished();
+    }
+    /// Counts a response of the round `flood_id` and what it taught,
+    /// the response keeps the round pending. A late response is counted in its ended
+    /// round, and completes it if it timed out
+    /// # Returns
+    /// `false` if the round is unknown
+    pub fn received(
+        &mut self,
+        flood_id: u64,
+        now: Instant,
+        new_nodes: usize,
+        new_links: usize,
+    ) -> bool {
+        let timeout = self.timeout;
+        let Some(round) = self.rounds.get_mut(&flood_id) else {
+            return false;
+        };
+        round.refresh(now, timeout);
+        if round.state == FloodRoundState::TimedOut {
+            round.state = FloodRoundState::Completed;
+        }
+        round.responses += 1;
+        round.new_nodes += new_nodes;
+        round.new_links += new_links;
+        round.last_response = Some(now);
+        true
+    }
+    /// Ends the rounds without responses for the timeout
+    pub fn refresh(&mut self, now: Instant) {
+        for round in self.rounds.values_mut() {
+            round.refresh(now, self.timeout);
+        }
+    }
+    fn forget_finished(&mut self) {
+        while self.rounds.len() > FLOOD_ROUND_HISTORY {
+            let Some(flood_id) = self
+                .rounds
+                .values()
+                .find(|round| round.state != FloodRoundState::Pending)
+                .map(|round| round.flood_id)
+            else {
+                return;
+            };
+            self.rounds.remove(&flood_id);
+        }
+    }
+}
+
+impl FloodRounds {
+    //getter/setter
+    /// The round `flood_id` as it is at `now`
+    pub fn get(&self, flood_id: u64, now: Instant) -> Option<FloodRound> {
+        let mut round = *self.rounds.get(&flood_id)?;
+        round.refresh(now, self.timeout);
+        Some(round)
+    }
+    /// At `now` none of the rounds `flood_ids` is pending and at least one completed
+    /// (the forgotten ones count as timed out)
+    pub fn completed(&self, flood_ids: Range<u64>, now: Instant) -> bool {
+        let mut completed = false;
+        for flood_id in flood_ids {
+            match self.get(flood_id, now).map(|round| round.state) {
+                Some(FloodRoundState::Pending) => return false,
+                Some(FloodRoundState::Completed) => completed = true,
+                Some(FloodRoundState::TimedOut) | None => {}
+            }
+        }
+        completed
+    }
+    /// The rounds as they are at `now`, ordered by flood id
+    pub fn rounds(&self, now: Instant) -> Vec<FloodRound> {
+        self.rounds
+            .values()
+            .map(|round| {
+                let mut round = *round;
+                round.refresh(now, self.timeout);
+                round
+            })
+            .collect()
+    }
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+}
@@ -0,0 +1,219 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::{collections::VecDeque, ops::Range, time::Instant};
+
+use super::network::Network;
+
+/// Number of flood rounds kept by default, see `Router::set_generation_limit`
+pub const TOPOLOGY_GENERATIONS: usize = 3;
+
+/// Summary of a topology generation, see `Router::generations`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenerationInfo {
+    /// First flood id of the round, `None` for the topology learned before any flood request
+    pub flood_id: Option<u64>,
+    /// When the flood round started
+    pub started: Instant,
+    /// Its flood round completed, it is used for routing before the older generations
+    pub complete: bool,
+    /// Number of known nodes
+    pub nodes: usize,
+}
+
+/// Topology learned in a flood round
+#[derive(Debug)]
+struct Generation {
+    network: Network,
+    /// Flood ids of the round, `None` for the topology learned before any flood request
+    flood_ids: Option<Range<u64>>,
+    started: Instant,
+    /// Set by `update_completion`, so that the generation stays complete
+    /// once its rounds are forgotten
+    complete: bool,
+}
+
+impl Generation {
+    /// `completed` tells whether the flood rounds of the given ids completed
+    fn is_complete(&self, completed: &impl Fn(&Range<u64>) -> bool) -> bool {
+        self.complete || self.flood_ids.as_ref().is_some_and(completed)
+    }
+    fn info(&self, completed: &impl Fn(&Range<u64>) -> bool) -> GenerationInfo {
+        GenerationInfo {
+            flood_id: self.flood_ids.as_ref().map(|flood_ids| flood_ids.start),
+            started: self.started,
+            complete: self.is_complete(completed),
+            nodes: self.network.get_node_number(),
+        }
+    }
+}
+
+/// The topologies learned in the last flood rounds, from the oldest to the newest.
+/// There is always at least one generation.
+/// A generation is complete once its flood rounds are (see `FloodRoundState::Completed`),
+/// the methods taking `completed` ask it for the rounds of the given flood ids
+#[derive(Debug)]
+pub struct Generations {
+    generations: VecDeque<Generation>,
+    limit: usize,
+    /// Epochs of the generations no longer kept, plus one for every change of the
+    /// generations, so that `epoch` never decreases
+    retired: u64,
+}
+
+impl Generations {
+    //constructor
+    pub fn new(network: Network, now: Instant) -> Self {
+        Self {
+            generations: VecDeque::from([Generation {
+                network,
+                flood_ids: None,
+                started: now,
+                complete: true,
+            }]),
+            limit: TOPOLOGY_GENERATIONS,
+            retired: 0,
+        }
+    }
+}
+
+impl Generations {
+    //methods
+    /// Starts the generation of the flood round `flood_ids` with `network`,
+    /// forgetting the oldest generations beyond the limit
+    pub fn start(
+        &mut self,
+        network: Network,
+        flood_ids: Range<u64>,
+        now: Instant,
+        completed: &impl Fn(&Range<u64>) -> bool,
+    ) {
+        self.generations.push_back(Generation {
+            network,
+            flood_ids: Some(flood_ids),
+            started: now,
+            complete: false,
+        });
+        self.retired += 1;
+        self.evict(completed);
+    }
+    /// Network of the round the flood `flood_id` belongs to.
+    /// `None` if that round has been forgotten or rolled back
+    pub fn for_flood(&mut self, flood_id: u64) -> Option<&mut Network> {
+        self.generations
+            .iter_mut()
+            .find(|generation| {
+                generation
+                    .flood_ids
+                    .as_ref()
+                    .is_some_and(|flood_ids| flood_ids.contains(&flood_id))
+            })
+            .map(|generation| &mut generation.network)
+    }
+    /// Marks as complete the generations whose flood rounds completed
+    pub fn update_completion(&mut self, completed: &impl Fn(&Range<u64>) -> bool) {
+        for generation in &mut self.generations {
+            if !generation.complete && generation.is_complete(completed) {
+                generation.complete = true;
+                // already counted by `epoch`
+                self.retired += 1;
+            }
+        }
+    }
+    /// Forgets the newest generation, routing goes back to the previous one
+    pub fn rollback(&mut self, completed: &impl Fn(&Range<u64>) -> bool) -> Option<GenerationInfo> {
+        if self.generations.len() < 2 {
+            return None;
+        }
+        let generation = self.generations.pop_back()?;
+        let info = generation.info(completed);
+        self.retire(&generation, completed);
+        Some(info)
+    }
+    fn evict(&mut self, completed: &impl Fn(&Range<u64>) -> bool) {
+        while self.generations.len() > self.limit {
+            // the network used for routing is kept
+            let index = usize::from(self.newest_complete(completed) == 0);
+            if let Some(generation) = self.generations.remove(index) {
+                self.retire(&generation, completed);
+            }
+        }
+    }
+    fn retire(&mut self, generation: &Generation, completed: &impl Fn(&Range<u64>) -> bool) {
+        self.retired += generation.network.epoch() + 1;
+        if !generation.complete && generation.is_complete(completed) {
+            self.retired += 1;
+        }
+    }
+    fn newest_complete(&self, completed: &impl Fn(&Range<u64>) -> bool) -> usize {
+        self.generations
+            .iter()
+            .rposition(|generation| generation.is_complete(completed))
+            .unwrap_or(0)
+    }
+}
+
+impl Generations {
+    //getter/setter
+    /// Newest complete generation, used for routing
+    pub fn current(&self, completed: &impl Fn(&Range<u64>) -> bool) -> &Network {
+        &self.generations[self.newest_complete(completed)].network
+    }
+    /// Newest generation, the one of the last flood round
+    pub fn newest(&self) -> &Network {
+        &self.generations[self.generations.len() - 1].network
+    }
+    /// The complete generations from the newest, then the incomplete ones from the newest
+    pub fn routing_order(&self, completed: &impl Fn(&Range<u64>) -> bool) -> Vec<&Network> {
+        let (complete, incomplete): (Vec<&Generation>, Vec<&Generation>) = self
+            .generations
+            .iter()
+            .rev()
+            .partition(|generation| generation.is_complete(completed));
+        complete
+            .into_iter()
+            .chain(incomplete)
+            .map(|generation| &generation.network)
+            .collect()
+    }
+    pub fn networks(&self) -> impl Iterator<Item = &Network> {
+        self.generations
+            .iter()
+            .map(|generation| &generation.network)
+    }
+    pub fn networks_mut(&mut self) -> impl Iterator<Item = &mut Network> {
+        self.generations
+            .iter_mut()
+            .map(|generation| &mut generation.network)
+    }
+    /// From the oldest to the newest
+    pub fn infos(&self, completed: &impl Fn(&Range<u64>) -> bool) -> Vec<GenerationInfo> {
+        self.generations
+            .iter()
+            .map(|generation| generation.info(completed))
+            .collect()
+    }
+    /// Increases every time one of the networks changes or the generations change,
+    /// a generation becoming complete included
+    pub fn epoch(&self, completed: &impl Fn(&Range<u64>) -> bool) -> u64 {
+        self.retired + self.completing(completed) + self.networks().map(Network::epoch).sum::<u64>()
+    }
+    /// Like `epoch`, but the costs changing in the networks do not count
+    pub fn structure_epoch(&self, completed: &impl Fn(&Range<u64>) -> bool) -> u64 {
+        self.retired
+            + self.completing(completed)
+            + self.networks().map(Network::structure_epoch).sum::<u64>()
+    }
+    /// Generations complete but not flagged yet by `update_completion`
+    fn completing(&self, completed: &impl Fn(&Range<u64>) -> bool) -> u64 {
+        self.generations
+            .iter()
+            .filter(|generation| !generation.complete && generation.is_complete(completed))
+            .count() as u64
+    }
+    /// Keeps at most `limit` generations, at least two: the one used for routing
+    /// and the one being learned
+    pub fn set_limit(&mut self, limit: usize, completed: &impl Fn(&Range<u64>) -> bool) {
+        self.limit = limit.max(2);
+        self.evict(completed);
+    }
+}
@@ -1,117 +1,559 @@
 	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
 
-use std::collections::HashSet;
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::error::Result;
+use crate::error::{PathTraceError, Result};
+use discovery::Discovery;
+use flood_rounds::FloodRounds;
+use generations::Generations;
 use network::Network;
+use pdr_estimator::PdrEstimator;
+use route_cache::RouteCache;
 use wg_2024::{
     network::NodeId,
     packet::{FloodResponse, NodeType},
 };
 
+mod discovery;
+mod flood_rounds;
+mod generations;
 mod network;
+mod pdr_estimator;
+mod route_cache;
+#[cfg(test)]
+mod test;
 
-#[derive(Debug)]
-enum NetworkStatus {
-    RequestSended,
-    ResponseReceived,
-    Swapped,
+pub use discovery::{ConvergenceRule, DiscoveryState, DiscoveryStatus};
+pub use flood_rounds::{FloodRound, FloodRoundState, FLOOD_ROUND_HISTORY, FLOOD_ROUND_TIMEOUT};
+pub use generations::{GenerationInfo, TOPOLOGY_GENERATIONS};
+pub use network::{
+    ArticulationPoint, Bridge, Clock, Confidence, DeliveryProbability, Disjointness, DropCount,
+    HopCount, LinkConfidence, LinkExpiry, LinkStats, ManualClock, Partition, PathTraceValidator,
+    RouteConstraints, RouteMetric, RoutingTable, SystemClock, TopologyAnalysis, TypeConflict,
+    WeightDecay, WeightedMetric, DECAY_REFRESHES_PER_HALF_LIFE, TOMBSTONE_LIFETIME,
+};
+pub use pdr_estimator::{PdrEstimate, PDR_EWMA_ALPHA};
+pub use route_cache::RouteCacheStats;
+
+/// Number of type conflicts remembered, see `Router::type_conflicts`
+pub const TYPE_CONFLICT_HISTORY: usize = 32;
+
+/// Called with the partition of the network when a crash or a removed neighbour
+/// cuts off part of it, see `Router::on_partition`
+pub struct PartitionCallback(Box<dyn FnMut(&Partition) + Send>);
+
+impl std::fmt::Debug for PartitionCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PartitionCallback")
+    }
 }
 
 #[derive(Debug)]
 pub struct NetworkHolder {
     id: NodeId,
     node_type: NodeType,
-    primary_network: Network,
-    secondary_network: Network,
-    status: NetworkStatus,
+    generations: Generations,
+    flood_rounds: FloodRounds,
+    discovery: Discovery,
+    /// The last nodes whose type was corrected by a path trace
+    type_conflicts: VecDeque<TypeConflict>,
+    /// Id of the next flood request: the older ones were requested before the
+    /// crashes and the removed links reported now
+    next_flood: u64,
+    tombstone_lifetime: Duration,
+    metric: Arc<dyn RouteMetric>,
+    pdr_estimator: PdrEstimator,
+    decay: WeightDecay,
+    clock: Arc<dyn Clock>,
+    expiry: LinkExpiry,
+    validator: PathTraceValidator,
+    route_cache: RouteCache,
+    /// Remove the nodes cut off from the root after a crash or a removed neighbour
+    prune_orphans: bool,
+    on_partition: Option<PartitionCallback>,
+    /// Orphaned components last given to the callback
+    reported_orphans: Vec<HashSet<NodeId>>,
 }
 
 impl NetworkHolder {
     //constructor
     #[must_use]
     pub fn new(root: NodeId, root_type: NodeType) -> Self {
+        Self::with_metric(root, root_type, Arc::new(DropCount))
+    }
+    #[must_use]
+    pub fn with_metric(root: NodeId, root_type: NodeType, metric: Arc<dyn RouteMetric>) -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
         Self {
             id: root,
             node_type: root_type,
-            primary_network: Network::new(root, root_type),
-            secondary_network: Network::new(root, root_type),
-            status: NetworkStatus::ResponseReceived,
+            generations: Generations::new(
+                Network::with_metric(root, root_type, metric.clone()),
+                clock.now(),
+            ),
+            flood_rounds: FloodRounds::new(),
+            discovery: Discovery::new(),
+            type_conflicts: VecDeque::new(),
+            next_flood: 0,
+            tombstone_lifetime: TOMBSTONE_LIFETIME,
+            metric,
+            pdr_estimator: PdrEstimator::new(),
+            decay: WeightDecay::default(),
+            clock,
+            expiry: LinkExpiry::default(),
+            validator: PathTraceValidator::new(root).with_repair(true),
+            route_cache: RouteCache::new(),
+            prune_orphans: false,
+            on_partition: None,
+            reported_orphans: Vec::new(),
         }
     }
 }
 
 impl NetworkHolder {
     //methods
-    pub fn received_flood_response(&mut self, response: &FloodResponse) {
-        match self.status {
-            NetworkStatus::RequestSended => {
-                self.status = NetworkStatus::ResponseReceived;
-                self.secondary_network = Network::new(self.id, self.node_type);
+    /// Updates the generation of the flood round the response belongs to and counts
+    /// what the response taught in the round.
+    /// The late responses of ended rounds (see `is_stale_flood`) are learned in their
+    /// generation too, the ones of forgotten or rolled back generations are ignored.
+    /// The types corrected by the response are corrected in every generation
+    /// # Returns
+    /// `false` if the response has not been learned, as the ones of a rolled back round
+    pub fn received_flood_response(&mut self, response: &FloodResponse) -> bool {
+        let mut learned = false;
+        let mut conflicts = Vec::new();
+        if let Some(network) = self.generations.for_flood(response.flood_id) {
+            let now = self.clock.now();
+            let (nodes, links) = (network.get_node_number(), network.get_link_number());
+            if let Ok(corrected) = network.update_from_flood_response(response, &self.validator) {
+                let new_nodes = network.get_node_number().saturating_sub(nodes);
+                let new_links = network.get_link_number().saturating_sub(links);
+                self.flood_rounds
+                    .received(response.flood_id, now, new_nodes, new_links);
+                self.discovery
+                    .received(response.flood_id, now, new_nodes + new_links > 0);
+                conflicts = corrected;
+                learned = true;
             }
-            NetworkStatus::ResponseReceived
-                if self.secondary_network.get_node_number() > self.primary_network.get_node_number() * 30 / 100 =>
-            {
-                self.swap_network();
+        }
+        for conflict in conflicts {
+            for network in self.generations.networks_mut() {
+                let _ = network.reconcile_type(conflict.id, conflict.observed, conflict.flood_id);
             }
-            NetworkStatus::ResponseReceived => {
-                self.status = NetworkStatus::ResponseReceived;
+            if self.type_conflicts.len() == TYPE_CONFLICT_HISTORY {
+                self.type_conflicts.pop_front();
             }
-            NetworkStatus::Swapped => {}
+            self.type_conflicts.push_back(conflict);
+        }
+        self.update_completion();
+        self.expire_links();
+        learned
+    }
+    /// The last nodes whose type was corrected by a path trace, from the oldest
+    pub fn type_conflicts(&self) -> Vec<TypeConflict> {
+        self.type_conflicts.iter().copied().collect()
+    }
+    /// Type of `id` in the newest generation that knows it
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        self.routing_order()
+            .into_iter()
+            .find_map(|network| network.node_type(id))
+    }
+    /// The floods `flood_ids` have been requested: a round is tracked for each of them
+    /// and their responses are learned in a new generation
+    pub fn asked_flood_request(&mut self, flood_ids: Range<u64>) {
+        let now = self.clock.now();
+        let network = self.new_network();
+        self.update_completion();
+        self.generations.start(
+            network,
+            flood_ids.clone(),
+            now,
+            &rounds_completed(&self.flood_rounds, now),
+        );
+        self.next_flood = self.next_flood.max(flood_ids.end);
+        self.discovery.start(flood_ids.clone(), now);
+        for flood_id in flood_ids {
+            self.flood_rounds.start(flood_id, now);
+        }
+        self.expire_links();
+    }
+    /// The round `flood_id` already ended or has been forgotten
+    pub fn is_stale_flood(&self, flood_id: u64) -> bool {
+        self.flood_round(flood_id)
+            .is_none_or(|round| round.state != FloodRoundState::Pending)
+    }
+    pub fn flood_round(&self, flood_id: u64) -> Option<FloodRound> {
+        self.flood_rounds.get(flood_id, self.clock.now())
+    }
+    /// The flood rounds remembered, ordered by flood id
+    pub fn flood_rounds(&self) -> Vec<FloodRound> {
+        self.flood_rounds.rounds(self.clock.now())
+    }
+    pub fn set_flood_round_timeout(&mut self, timeout: Duration) {
+        self.flood_rounds.set_timeout(timeout);
+    }
+    /// Whether the last flood requests converged, with the coverage of the generation
+    /// their responses are learned into (used for routing once their round completes)
+    pub fn discovery_state(&self) -> DiscoveryState {
+        let network = self.generations.newest();
+        self.discovery.state(
+            self.clock.now(),
+            network.get_node_number(),
+            network.get_link_number(),
+            network.get_reachable_server_list().len(),
+        )
+    }
+    pub fn set_convergence_rule(&mut self, rule: ConvergenceRule) {
+        self.discovery.set_rule(rule);
+    }
+    /// Forgets the newest generation, for when its flood round turned out to be partial
+    pub fn rollback_generation(&mut self) -> Option<GenerationInfo> {
+        let completed = rounds_completed(&self.flood_rounds, self.clock.now());
+        self.generations.rollback(&completed)
+    }
+    /// The kept generations, from the oldest to the newest
+    pub fn generations(&self) -> Vec<GenerationInfo> {
+        self.generations
+            .infos(&rounds_completed(&self.flood_rounds, self.clock.now()))
+    }
+    pub fn set_generation_limit(&mut self, limit: usize) {
+        let completed = rounds_completed(&self.flood_rounds, self.clock.now());
+        self.generations.set_limit(limit, &completed);
+    }
+    /// Flags the generations whose flood rounds completed, so that they stay complete
+    /// once the rounds are forgotten
+    fn update_completion(&mut self) {
+        let completed = rounds_completed(&self.flood_rounds, self.clock.now());
+        self.generations.update_completion(&completed);
+    }
+    /// Newest complete generation, used for routing
+    fn current(&self) -> &Network {
+        self.generations
+            .current(&rounds_completed(&self.flood_rounds, self.clock.now()))
+    }
+    /// See `Generations::routing_order`
+    fn routing_order(&self) -> Vec<&Network> {
+        self.generations
+            .routing_order(&rounds_completed(&self.flood_rounds, self.clock.now()))
+    }
+    /// Checks `path_trace` as the networks would before learning it
+    /// # Errors
+    /// - `Err(error)` if the trace would be rejected
+    pub fn validate_path_trace(
+        &self,
+        path_trace: &[(NodeId, NodeType)],
+    ) -> std::result::Result<(), PathTraceError> {
+        self.validator.validate(path_trace).map(|_| ())
+    }
+    /// When `repair` is false the malformed path traces are rejected instead of repaired
+    pub fn set_path_trace_repair(&mut self, repair: bool) {
+        self.validator = self.validator.with_repair(repair);
+    }
+    /// Ages out the links not confirmed recently, see `LinkExpiry`
+    pub fn expire_links(&mut self) {
+        self.update_completion();
+        for network in self.generations.networks_mut() {
+            network.expire_links();
+        }
+    }
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock.clone();
+        for network in self.generations.networks_mut() {
+            network.set_clock(clock.clone());
         }
-        self.primary_network
-            .update_from_path_trace(&response.path_trace);
-        self.secondary_network
-            .update_from_path_trace(&response.path_trace);
     }
-    pub fn asked_flood_request(&mut self) {
-        if !matches!(self.status, NetworkStatus::Swapped) {
-            self.swap_network();
+    pub fn set_link_expiry(&mut self, expiry: LinkExpiry) {
+        self.expiry = expiry;
+        for network in self.generations.networks_mut() {
+            network.set_link_expiry(expiry);
         }
-        self.status = NetworkStatus::RequestSended;
+        self.expire_links();
+    }
+    /// Last time `id` was seen in a path trace, in any generation
+    pub fn last_confirmed(&self, id: NodeId) -> Option<Instant> {
+        self.generations
+            .networks()
+            .filter_map(|network| network.last_confirmed(id))
+            .max()
+    }
+    /// Last time the link was seen in a path trace, in any generation
+    pub fn link_last_confirmed(&self, id1: NodeId, id2: NodeId) -> Option<Instant> {
+        self.generations
+            .networks()
+            .filter_map(|network| network.link_last_confirmed(id1, id2))
+            .max()
     }
+    /// Confidence of the link in the network used for routing
+    pub fn link_confidence(&self, id1: NodeId, id2: NodeId) -> Option<LinkConfidence> {
+        self.current().link_confidence(id1, id2)
+    }
+    /// `id` is removed and buried: the responses to the floods requested before
+    /// the crash cannot add it again, see `set_tombstone_lifetime`
     pub fn drone_crashed(&mut self, id: NodeId) {
-        let _ = self.primary_network.remove_node(id);
-        let _ = self.secondary_network.remove_node(id);
+        for network in self.generations.networks_mut() {
+            let _ = network.remove_node(id);
+            network.bury_node(id, self.next_flood);
+        }
+        self.check_partition();
     }
     pub fn dropped_fragment(&mut self, id1: NodeId) {
-        let _ = self.primary_network.increment_weight(id1);
-        let _ = self.secondary_network.increment_weight(id1);
+        for network in self.generations.networks_mut() {
+            let _ = network.increment_weight(id1);
+        }
+    }
+    pub fn dropped_fragment_on_link(&mut self, id1: NodeId, id2: NodeId) {
+        for network in self.generations.networks_mut() {
+            let _ = network.increment_link_weight(id1, id2);
+        }
     }
     pub fn add_neighbour(&mut self, id: NodeId) {
-        let _ = self.primary_network.add_neighbour(id);
-        let _ = self.secondary_network.add_neighbour(id);
+        for network in self.generations.networks_mut() {
+            let _ = network.add_neighbour(id);
+        }
     }
+    /// The link with `id` is removed and buried, as the crashed drones
     pub fn remove_neighbour(&mut self, id: NodeId) {
-        let _ = self.primary_network.remove_neighbour_link(id);
-        let _ = self.secondary_network.remove_neighbour_link(id);
+        for network in self.generations.networks_mut() {
+            let _ = network.remove_neighbour_link(id);
+            network.bury_link(self.id, id, self.next_flood);
+        }
+        self.check_partition();
+    }
+    /// The link between `id1` and `id2` does not exist (anymore), it is removed and
+    /// buried as the neighbour links
+    pub fn remove_link(&mut self, id1: NodeId, id2: NodeId) {
+        for network in self.generations.networks_mut() {
+            let _ = network.remove_link(id1, id2);
+            network.bury_link(id1, id2, self.next_flood);
+        }
+        self.check_partition();
+    }
+    /// Corrects the type of `id` in every generation that knows it, as reported by
+    /// a `Nack`: the responses to the floods already requested do not change it back.
+    /// The type of the root never changes
+    pub fn set_node_type(&mut self, id: NodeId, node_type: NodeType) {
+        for network in self.generations.networks_mut() {
+            let _ = network.reconcile_type(id, node_type, Some(self.next_flood));
+        }
+    }
+    /// How long the crashed drones and the removed links stay buried
+    pub fn set_tombstone_lifetime(&mut self, lifetime: Duration) {
+        self.tombstone_lifetime = lifetime;
+        for network in self.generations.networks_mut() {
+            network.set_tombstone_lifetime(lifetime);
+        }
+    }
+    /// Reports the partition of the network used for routing to the callback if the part
+    /// cut off changed, then prunes the orphaned nodes if asked to
+    fn check_partition(&mut self) {
+        let partition = self.current().partition();
+        if partition.orphaned == self.reported_orphans {
+            return;
+        }
+        self.reported_orphans.clone_from(&partition.orphaned);
+        if !partition.is_partitioned() {
+            return;
+        }
+        if let Some(PartitionCallback(callback)) = self.on_partition.as_mut() {
+            callback(&partition);
+        }
+        if self.prune_orphans {
+            self.prune_orphans();
+        }
+    }
+    fn prune_orphans(&mut self) {
+        for network in self.generations.networks_mut() {
+            network.prune_orphans();
+        }
+        self.reported_orphans.clear();
+    }
+    pub fn set_prune_orphans(&mut self, prune: bool) {
+        self.prune_orphans = prune;
+        if prune {
+            self.prune_orphans();
+        }
+    }
+    pub fn on_partition(&mut self, callback: impl FnMut(&Partition) + Send + 'static) {
+        self.on_partition = Some(PartitionCallback(Box::new(callback)));
     }
     /// Returns the list of server in the network, used to determine which server is Chat
-    /// and which is Media/Text  
+    /// and which is Media/Text.
+    /// Servers cut off from the root in every generation are not listed
     pub fn get_server_list(&self) -> HashSet<NodeId> {
-        self.primary_network
-            .get_server_list()
-            .into_iter()
-            .chain(self.secondary_network.get_server_list())
+        self.generations
+            .networks()
+            .flat_map(Network::get_reachable_server_list)
+            .collect()
+    }
+    /// Known servers without a route in any generation
+    pub fn get_unreachable_server_list(&self) -> HashSet<NodeId> {
+        let reachable = self.get_server_list();
+        self.generations
+            .networks()
+            .flat_map(Network::get_server_list)
+            .filter(|id| !reachable.contains(id))
             .collect()
     }
-    fn swap_network(&mut self) {
-        std::mem::swap(&mut self.primary_network, &mut self.secondary_network);
-        self.status = NetworkStatus::Swapped;
+    /// Partition of the network used for routing
+    pub fn partition(&self) -> Partition {
+        self.current().partition()
+    }
+    /// Changes how fast the drops on the links are forgotten
+    pub fn set_weight_decay(&mut self, decay: WeightDecay) {
+        self.decay = decay;
+        for network in self.generations.networks_mut() {
+            network.set_weight_decay(decay);
+        }
+    }
+    /// The route through `hops` has been used to send a packet
+    pub fn packet_sent(&mut self, hops: &[NodeId]) {
+        self.pdr_estimator.packet_sent(hops);
     }
+    /// The packet sent through `hops` has been acked by the destination
+    pub fn packet_acked(&mut self, hops: &[NodeId]) {
+        for id in self.pdr_estimator.packet_acked(hops) {
+            self.update_pdr(id);
+        }
+    }
+    /// `id` notified the drop of a packet
+    pub fn packet_dropped_at(&mut self, id: NodeId) {
+        self.pdr_estimator.packet_dropped_at(id);
+        self.update_pdr(id);
+    }
+    pub fn estimated_pdr(&self, id: NodeId) -> Option<PdrEstimate> {
+        self.pdr_estimator.estimate(id)
+    }
+    fn update_pdr(&mut self, id: NodeId) {
+        if let Some(estimate) = self.pdr_estimator.estimate(id) {
+            for network in self.generations.networks_mut() {
+                network.set_pdr(id, estimate.mean);
+            }
+        }
+    }
+    /// Empty network that already knows the estimated pdr of the drones, the decay,
+    /// the clock, the expiry of the links, the confidence in them and the drops recorded
+    fn new_network(&self) -> Network {
+        let mut network = Network::with_metric(self.id, self.node_type, self.metric.clone());
+        network.inherit_confidence(self.generations.newest());
+        network.inherit_weights(self.generations.newest());
+        network.set_weight_decay(self.decay);
+        network.set_clock(self.clock.clone());
+        network.set_link_expiry(self.expiry);
+        network.set_tombstone_lifetime(self.tombstone_lifetime);
+        for (id, estimate) in self.pdr_estimator.estimates() {
+            network.set_pdr(id, estimate.mean);
+        }
+        network
+    }
+    /// Result of `route` on the first generation where it succeeds, in routing order
+    fn route_with<T>(&self, route: impl Fn(&Network) -> Result<T>) -> Result<T> {
+        let mut result = route(self.current());
+        for network in self.routing_order().into_iter().skip(1) {
+            if result.is_ok() {
+                break;
+            }
+            result = route(network);
+        }
+        result
+    }
+}
+
+/// Tells `Generations` whether the flood rounds of a generation completed at `now`
+fn rounds_completed(rounds: &FloodRounds, now: Instant) -> impl Fn(&Range<u64>) -> bool + '_ {
+    move |flood_ids| rounds.completed(flood_ids.clone(), now)
 }
 
 impl NetworkHolder {
     // getter setter
+    /// Path from the newest complete generation, or else from the older ones
     pub fn get_path(&self, destination: NodeId) -> Result<Vec<NodeId>> {
-        let path = self
-            .primary_network
-            .get_routes(destination)
-            .or_else(|_| self.secondary_network.get_routes(destination))?;
-        Ok(path)
+        self.route_cache
+            .get_or_compute(self.topology_epoch(), destination, || {
+                self.route_with(|network| network.get_routes(destination))
+            })
+    }
+    /// Increases every time the topology or the weights of one of the generations change,
+    /// or a generation is started, completed or forgotten
+    pub fn topology_epoch(&self) -> u64 {
+        self.generations
+            .epoch(&rounds_completed(&self.flood_rounds, self.clock.now()))
+    }
+    /// Like `topology_epoch`, but changes only when nodes or links are added or removed,
+    /// nodes change type or the generations change, not when the costs change
+    pub fn structure_epoch(&self) -> u64 {
+        self.generations
+            .structure_epoch(&rounds_completed(&self.flood_rounds, self.clock.now()))
     }
-    pub fn get_multiple_paths(&self, destination: NodeId) -> Vec<Vec<NodeId>> {
-        self.primary_network.multiple_paths(destination)
+    pub fn route_cache_stats(&self) -> RouteCacheStats {
+        self.route_cache.stats()
+    }
+    /// Shortest path tree of the network used for routing
+    pub fn routing_table(&self) -> Result<Arc<RoutingTable>> {
+        self.current().routing_table()
+    }
+    /// Single points of failure of the network used for routing
+    pub fn topology_analysis(&self) -> TopologyAnalysis {
+        self.current().analyze_topology()
+    }
+    /// `get_k_best_paths`, empty if the destination is unreachable
+    pub fn get_multiple_paths(&self, destination: NodeId, k: usize) -> Vec<Vec<NodeId>> {
+        self.get_k_best_paths(destination, k).unwrap_or_default()
+    }
+    /// Disjoint paths from the generation that finds the most, preferring the newest
+    /// complete one
+    pub fn get_disjoint_paths(
+        &self,
+        destination: NodeId,
+        count: usize,
+        disjointness: Disjointness,
+    ) -> Vec<Vec<NodeId>> {
+        let mut best = Vec::new();
+        for network in self.routing_order() {
+            let paths = network.disjoint_paths(destination, count, disjointness);
+            if paths.len() > best.len() {
+                best = paths;
+            }
+            if best.len() >= count {
+                break;
+            }
+        }
+        best
+    }
+    /// Cheapest path satisfying `constraints`, from the newest complete generation
+    /// or else from the older ones
+    pub fn get_constrained_path(
+        &self,
+        destination: NodeId,
+        constraints: &RouteConstraints,
+    ) -> Result<Vec<NodeId>> {
+        self.route_with(|network| network.constrained_path(destination, constraints))
+    }
+    /// The `k` cheapest paths with their costs, from the newest complete generation
+    /// or else from the older ones
+    pub fn get_candidate_paths(
+        &self,
+        destination: NodeId,
+        k: usize,
+    ) -> Result<Vec<(Vec<NodeId>, u64)>> {
+        self.route_with(|network| {
+            network.k_shortest_paths(destination, k).map(|paths| {
+                paths
+                    .into_iter()
+                    .map(|path| {
+                        let cost = network.path_cost(&path);
+                        (path, cost)
+                    })
+                    .collect()
+            })
+        })
+    }
+    pub fn get_k_best_paths(&self, destination: NodeId, k: usize) -> Result<Vec<Vec<NodeId>>> {
+        self.route_with(|network| network.k_shortest_paths(destination, k))
     }
 }
-
@@ -0,0 +1,157 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{Network, NodeSet, MAX_NODES};
+use std::collections::HashSet;
+use wg_2024::network::NodeId;
+
+/// A drone whose crash would cut off part of the network from the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticulationPoint {
+    pub id: NodeId,
+    /// Nodes that would become unreachable
+    pub unreachable: HashSet<NodeId>,
+}
+
+/// A link whose removal would cut off part of the network from the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bridge {
+    /// `(parent, child)`, the parent being the endpoint closer to the root
+    pub link: (NodeId, NodeId),
+    /// Nodes that would become unreachable
+    pub unreachable: HashSet<NodeId>,
+}
+
+/// Single points of failure of the network as seen from the root, sorted by id
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopologyAnalysis {
+    pub articulation_points: Vec<ArticulationPoint>,
+    pub bridges: Vec<Bridge>,
+}
+
+impl TopologyAnalysis {
+    //getter/setter
+    /// Drones whose crash alone would make `destination` unreachable
+    #[must_use]
+    pub fn single_points_of_failure(&self, destination: NodeId) -> Vec<NodeId> {
+        self.articulation_points
+            .iter()
+            .filter(|point| point.unreachable.contains(&destination))
+            .map(|point| point.id)
+            .collect()
+    }
+}
+
+/// State of Tarjan's depth first search
+struct Tarjan {
+    /// Discovery time, 0 if not visited
+    discovery: [usize; MAX_NODES],
+    low: [usize; MAX_NODES],
+    parent: [Option<NodeId>; MAX_NODES],
+    /// Visited nodes in preorder, the subtree of `u` is `preorder[discovery[u] - 1..end[u]]`
+    preorder: Vec<NodeId>,
+    end: [usize; MAX_NODES],
+}
+
+impl Network {
+    /// Articulation points and bridges of the part of the network reachable from the root,
+    /// computed with Tarjan's algorithm.
+    /// Only the root and the drones forward packets, so the search runs on them and then
+    /// a client or a server is cut off when all its forwarding neighbours are
+    pub fn analyze_topology(&self) -> TopologyAnalysis {
+        let mut tarjan = Tarjan {
+            discovery: [0; MAX_NODES],
+            low: [0; MAX_NODES],
+            parent: [None; MAX_NODES],
+            preorder: Vec::new(),
+            end: [0; MAX_NODES],
+        };
+        self.visit(&mut tarjan, self.root);
+        let visited = |id: NodeId| tarjan.discovery[usize::from(id)] != 0;
+        let subtree = |id: NodeId| -> NodeSet {
+            tarjan.preorder[tarjan.discovery[usize::from(id)] - 1..tarjan.end[usize::from(id)]]
+                .iter()
+                .copied()
+                .collect()
+        };
+        // clients and servers with the visited nodes that can forward to them
+        let leaves: Vec<(NodeId, NodeSet)> = self
+            .iter_nodes()
+            .filter(|(id, _)| !self.expandable(*id))
+            .map(|(id, node)| {
+                let forwarders: NodeSet = node.neighbours.iter().filter(|n| visited(*n)).collect();
+                (id, forwarders)
+            })
+            .filter(|(_, forwarders)| !forwarders.is_empty())
+            .collect();
+        let cut_off = |cut: NodeSet, failed: Option<NodeId>| -> HashSet<NodeId> {
+            let leaves = leaves.iter().filter(|(_, forwarders)| {
+                forwarders
+                    .iter()
+                    .all(|n| cut.contains(n) || Some(n) == failed)
+            });
+            cut.iter().chain(leaves.map(|(id, _)| *id)).collect()
+        };
+
+        let mut analysis = TopologyAnalysis::default();
+        for &id in tarjan.preorder.iter().filter(|id| **id != self.root) {
+            let mut cut = NodeSet::new();
+            for child in tarjan.preorder.iter().copied() {
+                let index = usize::from(child);
+                if tarjan.parent[index] == Some(id)
+                    && tarjan.low[index] >= tarjan.discovery[usize::from(id)]
+                {
+                    cut.extend(subtree(child));
+                }
+            }
+            let unreachable = cut_off(cut, Some(id));
+            if !unreachable.is_empty() {
+                analysis
+                    .articulation_points
+                    .push(ArticulationPoint { id, unreachable });
+            }
+        }
+        for &child in &tarjan.preorder {
+            let index = usize::from(child);
+            if let Some(parent) = tarjan.parent[index] {
+                if tarjan.low[index] > tarjan.discovery[usize::from(parent)] {
+                    analysis.bridges.push(Bridge {
+                        link: (parent, child),
+                        unreachable: cut_off(subtree(child), None),
+                    });
+                }
+            }
+        }
+        for (id, forwarders) in &leaves {
+            let mut iter = forwarders.iter();
+            if let (Some(forwarder), None) = (iter.next(), iter.next()) {
+                analysis.bridges.push(Bridge {
+                    link: (forwarder, *id),
+                    unreachable: HashSet::from([*id]),
+                });
+            }
+        }
+        analysis.articulation_points.sort_by_key(|point| point.id);
+        analysis.bridges.sort_by_key(|bridge| bridge.link);
+        analysis
+    }
+    fn visit(&self, tarjan: &mut Tarjan, u: NodeId) {
+        let index = usize::from(u);
+        tarjan.preorder.push(u);
+        tarjan.discovery[index] = tarjan.preorder.len();
+        tarjan.low[index] = tarjan.discovery[index];
+        for v in self.get(u).map_or(NodeSet::new(), |node| node.neighbours) {
+            if !self.expandable(v) || tarjan.parent[index] == Some(v) {
+                continue;
+            }
+            let v_index = usize::from(v);
+            if tarjan.discovery[v_index] == 0 {
+                tarjan.parent[v_index] = Some(u);
+                self.visit(tarjan, v);
+                tarjan.low[index] = tarjan.low[index].min(tarjan.low[v_index]);
+            } else {
+                tarjan.low[index] = tarjan.low[index].min(tarjan.discovery[v_index]);
+            }
+        }
+        tarjan.end[index] = tarjan.preorder.len();
+    }
+}
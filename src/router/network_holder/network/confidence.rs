@@ -0,0 +1,75 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{dynamic_tree::TopologyChange, Network};
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// How many times a link has been confirmed by flood responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LinkConfidence {
+    /// Flood responses whose path trace contained the link
+    pub responses: u32,
+    /// Distinct flood ids among those responses
+    pub floods: u32,
+}
+
+impl Network {
+    //methods
+    /// Counts the response (and its flood) once for every link of `path_trace`,
+    /// which starts at the root.
+    /// Flood ids are increasing, so a flood is new for a link if its id is higher
+    /// than the last one that confirmed it.
+    /// Only a new flood changes the cost of the link, another response of the same flood doesn't
+    pub(super) fn observe_links(
+        &mut self,
+        path_trace: &[(NodeId, NodeType)],
+        flood_id: Option<u64>,
+    ) {
+        let mut links: Vec<(NodeId, NodeId)> = path_trace
+            .windows(2)
+            .map(|pair| (pair[0].0, pair[1].0))
+            .map(|(id1, id2)| (id1.min(id2), id1.max(id2)))
+            .collect();
+        links.sort_unstable();
+        links.dedup();
+        for (id1, id2) in links {
+            let linked = self
+                .get(id1)
+                .is_ok_and(|node| node.neighbours.contains(id2));
+            let Some(state) = self.link_state.get_mut(id1, id2).filter(|_| linked) else {
+                continue;
+            };
+            state.confidence.responses = state.confidence.responses.saturating_add(1);
+            let Some(flood_id) = flood_id else {
+                continue;
+            };
+            if state.last_flood.is_none_or(|last| flood_id > last) {
+                state.confidence.floods = state.confidence.floods.saturating_add(1);
+                state.last_flood = Some(flood_id);
+                // the cost can only decrease, as for a new link
+                self.weight_changed(TopologyChange::LinkAdded(id1, id2));
+            }
+        }
+    }
+}
+
+impl Network {
+    //getter/setter
+    /// Starts from the confidence that `previous` (the generation learned before)
+    /// has in its links, so that the floods confirming a link are counted across rounds.
+    /// To be called before learning any link
+    pub fn inherit_confidence(&mut self, previous: &Network) {
+        self.link_state = previous.link_state.clone();
+    }
+    /// `None` if the link is not in the network
+    pub fn link_confidence(&self, id1: NodeId, id2: NodeId) -> Option<LinkConfidence> {
+        let linked = self
+            .get(id1)
+            .is_ok_and(|node| node.neighbours.contains(id2));
+        linked.then(|| {
+            self.link_state
+                .get(id1, id2)
+                .map(|state| state.confidence)
+                .unwrap_or_default()
+        })
+    }
+}
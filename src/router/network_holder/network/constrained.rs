@@ -0,0 +1,188 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{Network, NodeSet, Path, MAX_NODES};
+use crate::error::{Result, RouterError::RouteNotFound};
+use std::collections::HashSet;
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// Restrictions on the route returned by `Router::get_source_routing_header_with`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteConstraints {
+    /// Nodes the route must not cross
+    pub avoid: HashSet<NodeId>,
+    /// Drones the route must cross, in this order
+    pub waypoints: Vec<NodeId>,
+    /// Maximum number of links in the route
+    pub max_hops: Option<usize>,
+}
+
+impl RouteConstraints {
+    //constructor
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RouteConstraints {
+    //methods
+    #[must_use]
+    pub fn avoiding(mut self, id: NodeId) -> Self {
+        self.avoid.insert(id);
+        self
+    }
+    /// Appends `id` to the waypoints
+    #[must_use]
+    pub fn through(mut self, id: NodeId) -> Self {
+        self.waypoints.push(id);
+        self
+    }
+    #[must_use]
+    pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+        self.max_hops = Some(max_hops);
+        self
+    }
+}
+
+/// Node of the search graph: a node of the network together with
+/// the number of waypoints already crossed (the stage)
+type State = usize;
+
+impl Network {
+    /// Cheapest route from the root to `destination` satisfying `constraints`.
+    /// The search is a Bellman-Ford bounded by `max_hops` over the pairs (node, stage),
+    /// a waypoint being crossed only at its stage, so the order of the waypoints is kept.
+    /// When the route found goes back on a drone crossed at an earlier stage,
+    /// that drone is banned from the later stage and the search is repeated.
+    /// As in `get_routes` only the endpoints of a path can be a client or a server.
+    /// # Errors
+    /// - `Err(IdNotFound)` if a waypoint or the destination is not in the network
+    /// - `Err(RouteNotFound)` if no loop-free route satisfying the constraints is found
+    pub fn constrained_path(
+        &self,
+        destination: NodeId,
+        constraints: &RouteConstraints,
+    ) -> Result<Path> {
+        let not_found = || Box::new(RouteNotFound { destination });
+        for &id in &constraints.waypoints {
+            let node = self.get(id)?;
+            if !matches!(node.node_type, NodeType::Drone) || constraints.avoid.contains(&id) {
+                return Err(not_found());
+            }
+        }
+        self.get(destination)?;
+        if constraints.avoid.contains(&destination) {
+            return Err(not_found());
+        }
+
+        // banned[stage] are the nodes that can not be crossed at that stage
+        let mut banned = vec![NodeSet::new(); constraints.waypoints.len() + 1];
+        loop {
+            let walk = self
+                .constrained_walk(destination, constraints, &banned)
+                .ok_or_else(not_found)?;
+            let mut first_stage = [None; MAX_NODES];
+            let repeated = walk.iter().find_map(|state| {
+                let (stage, id) = (state / MAX_NODES, node_of(*state));
+                match first_stage[usize::from(id)] {
+                    Some(first) if first < stage => Some((stage, id)),
+                    _ => {
+                        first_stage[usize::from(id)] = Some(stage);
+                        None
+                    }
+                }
+            });
+            match repeated {
+                Some((stage, id)) => {
+                    banned[stage].insert(id);
+                }
+                None => return Ok(walk.into_iter().map(node_of).collect()),
+            }
+        }
+    }
+    /// Cheapest walk of states from the root to `destination` at the last stage,
+    /// `None` if there is none within `constraints.max_hops`
+    fn constrained_walk(
+        &self,
+        destination: NodeId,
+        constraints: &RouteConstraints,
+        banned: &[NodeSet],
+    ) -> Option<Vec<State>> {
+        let waypoints = &constraints.waypoints;
+        let stages = waypoints.len() + 1;
+        let target: State = waypoints.len() * MAX_NODES + usize::from(destination);
+        let max_hops = constraints
+            .max_hops
+            .unwrap_or(usize::MAX)
+            .min(stages * MAX_NODES);
+        let waypoint_set: NodeSet = waypoints.iter().copied().collect();
+
+        let mut cost = vec![u64::MAX; stages * MAX_NODES];
+        // improvements of every state: (hops, parent), used to rebuild the walk
+        let mut history: Vec<Vec<(usize, Option<State>)>> = vec![Vec::new(); stages * MAX_NODES];
+        let source = usize::from(self.root);
+        cost[source] = 0;
+        history[source].push((0, None));
+
+        let mut frontier = vec![source];
+        let mut hops = 0;
+        while hops < max_hops && !frontier.is_empty() {
+            hops += 1;
+            let previous = cost.clone();
+            let mut improved = Vec::new();
+            for state in frontier {
+                let (stage, u) = (state / MAX_NODES, node_of(state));
+                if state == target || !self.expandable(u) {
+                    continue;
+                }
+                for v in self.get(u).ok()?.neighbours {
+                    if v == self.root || constraints.avoid.contains(&v) || !self.contains_id(v) {
+                        continue;
+                    }
+                    let next_stage = if waypoints.get(stage) == Some(&v) {
+                        stage + 1
+                    } else if waypoint_set.contains(v) {
+                        continue;
+                    } else {
+                        stage
+                    };
+                    let next = next_stage * MAX_NODES + usize::from(v);
+                    if banned[next_stage].contains(v)
+                        || (next != target && (v == destination || !self.expandable(v)))
+                    {
+                        continue;
+                    }
+                    let new_cost = previous[state].saturating_add(self.link_cost(u, v));
+                    if new_cost < cost[next] {
+                        if cost[next] == previous[next] {
+                            improved.push(next);
+                        }
+                        cost[next] = new_cost;
+                        history[next].push((hops, Some(state)));
+                    }
+                }
+            }
+            frontier = improved;
+        }
+        if cost[target] == u64::MAX {
+            return None;
+        }
+
+        let mut walk = Vec::new();
+        let mut state = Some(target);
+        while let Some(current) = state {
+            let &(improved_at, parent) =
+                history[current].iter().rev().find(|(at, _)| *at <= hops)?;
+            walk.push(current);
+            state = parent;
+            hops = improved_at.saturating_sub(1);
+        }
+        walk.reverse();
+        Some(walk)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn node_of(state: State) -> NodeId {
+    (state % MAX_NODES) as NodeId
+}
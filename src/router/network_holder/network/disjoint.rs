@@ -0,0 +1,212 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{Network, NodeSet, Path};
+use std::collections::{HashMap, VecDeque};
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// What the paths returned by [`Network::disjoint_paths`] can not share
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disjointness {
+    /// No intermediate drone in common (implies [`Disjointness::Link`])
+    Node,
+    /// No link in common, intermediate drones can be shared
+    Link,
+}
+
+#[derive(Debug, Clone)]
+struct Arc {
+    to: usize,
+    capacity: i64,
+    cost: i64,
+    /// Index of the reverse arc in `graph[to]`
+    reverse: usize,
+    /// `false` for the residual arcs
+    original: bool,
+}
+
+/// Residual graph used by the successive shortest path algorithm.
+/// Every node `i` is splitted in `2 * i` (in) and `2 * i + 1` (out).
+#[derive(Debug)]
+struct FlowGraph {
+    graph: Vec<Vec<Arc>>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        Self {
+            graph: vec![Vec::new(); nodes * 2],
+        }
+    }
+    fn add_arc(&mut self, from: usize, to: usize, capacity: i64, cost: i64) {
+        let reverse_from = self.graph[to].len();
+        let reverse_to = self.graph[from].len();
+        self.graph[from].push(Arc {
+            to,
+            capacity,
+            cost,
+            reverse: reverse_from,
+            original: true,
+        });
+        self.graph[to].push(Arc {
+            to: from,
+            capacity: 0,
+            cost: -cost,
+            reverse: reverse_to,
+            original: false,
+        });
+    }
+    /// Bellman-Ford (queue based) on the residual graph, needed because
+    /// residual arcs have negative costs.
+    /// Returns for every vertex the `(vertex, arc index)` used to reach it
+    fn shortest_augmenting_path(
+        &self,
+        source: usize,
+        sink: usize,
+    ) -> Option<Vec<Option<(usize, usize)>>> {
+        let mut distance = vec![i64::MAX; self.graph.len()];
+        let mut parent = vec![None; self.graph.len()];
+        let mut in_queue = vec![false; self.graph.len()];
+        let mut queue = VecDeque::new();
+        distance[source] = 0;
+        queue.push_back(source);
+        in_queue[source] = true;
+
+        while let Some(u) = queue.pop_front() {
+            in_queue[u] = false;
+            for (index, arc) in self.graph[u].iter().enumerate() {
+                if arc.capacity <= 0 {
+                    continue;
+                }
+                let new_distance = distance[u].saturating_add(arc.cost);
+                if new_distance < distance[arc.to] {
+                    distance[arc.to] = new_distance;
+                    parent[arc.to] = Some((u, index));
+                    if !in_queue[arc.to] {
+                        in_queue[arc.to] = true;
+                        queue.push_back(arc.to);
+                    }
+                }
+            }
+        }
+        (distance[sink] != i64::MAX).then_some(parent)
+    }
+    /// Push one unit of flow along the cheapest augmenting path
+    /// # Returns
+    /// `false` if the sink is no longer reachable
+    fn augment(&mut self, source: usize, sink: usize) -> bool {
+        let Some(parent) = self.shortest_augmenting_path(source, sink) else {
+            return false;
+        };
+        let mut current = sink;
+        while let Some((u, index)) = parent[current] {
+            let reverse = self.graph[u][index].reverse;
+            self.graph[u][index].capacity -= 1;
+            self.graph[current][reverse].capacity += 1;
+            current = u;
+        }
+        true
+    }
+    /// Units of flow on the original arc `graph[u][index]`
+    fn flow(&self, u: usize, index: usize) -> i64 {
+        let arc = &self.graph[u][index];
+        self.graph[arc.to][arc.reverse].capacity
+    }
+}
+
+impl Network {
+    /// Suurballe/Bhandari: computes up to `count` paths from the root to `destination`
+    /// that do not share intermediate drones (or links, depending on `disjointness`),
+    /// minimizing the total cost of the set.
+    /// As in `get_routes` only the endpoints of a path can be a client or a server.
+    /// # Returns
+    /// The paths found, fewer than `count` if the topology does not allow more
+    pub fn disjoint_paths(
+        &self,
+        destination: NodeId,
+        count: usize,
+        disjointness: Disjointness,
+    ) -> Vec<Path> {
+        if count == 0 || destination == self.root || !self.contains_id(destination) {
+            return Vec::new();
+        }
+        let ids: Vec<NodeId> = self
+            .iter_nodes()
+            .filter(|(id, node)| {
+                *id == self.root || *id == destination || matches!(node.node_type, NodeType::Drone)
+            })
+            .map(|(id, _)| id)
+            .collect();
+        let index: HashMap<NodeId, usize> =
+            ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let unlimited = i64::try_from(count).unwrap_or(i64::MAX);
+
+        let mut flow_graph = FlowGraph::new(ids.len());
+        for (i, id) in ids.iter().enumerate() {
+            let capacity = match disjointness {
+                Disjointness::Node if *id != self.root && *id != destination => 1,
+                _ => unlimited,
+            };
+            flow_graph.add_arc(2 * i, 2 * i + 1, capacity, 0);
+            for neighbour in self.get(*id).map_or(NodeSet::new(), |node| node.neighbours) {
+                let Some(&j) = index.get(&neighbour) else {
+                    continue;
+                };
+                if i == j {
+                    continue;
+                }
+                let cost = i64::try_from(self.link_cost(*id, neighbour)).unwrap_or(i64::MAX / 4);
+                flow_graph.add_arc(2 * i + 1, 2 * j, 1, cost);
+            }
+        }
+
+        let source = 2 * index[&self.root] + 1;
+        let sink = 2 * index[&destination];
+        let mut found = 0;
+        while found < count && flow_graph.augment(source, sink) {
+            found += 1;
+        }
+
+        // net flow on every link, one unit in each direction is the same as no flow
+        let mut link_flow: HashMap<(NodeId, NodeId), i64> = HashMap::new();
+        for (u, arcs) in flow_graph
+            .graph
+            .iter()
+            .enumerate()
+            .filter(|(u, _)| u % 2 == 1)
+        {
+            for (arc_index, arc) in arcs.iter().enumerate() {
+                if arc.original && arc.to != u - 1 && flow_graph.flow(u, arc_index) > 0 {
+                    *link_flow.entry((ids[u / 2], ids[arc.to / 2])).or_insert(0) += 1;
+                    *link_flow.entry((ids[arc.to / 2], ids[u / 2])).or_insert(0) -= 1;
+                }
+            }
+        }
+        let mut paths: Vec<Path> = (0..found)
+            .filter_map(|_| self.extract_path(&mut link_flow, destination))
+            .collect();
+        paths.sort_by_key(|path| (self.path_cost(path), path.len()));
+        paths
+    }
+    /// Follows (and consumes) one unit of flow from the root to `destination`,
+    /// cutting the loops that link disjoint paths can contain
+    fn extract_path(
+        &self,
+        link_flow: &mut HashMap<(NodeId, NodeId), i64>,
+        destination: NodeId,
+    ) -> Option<Path> {
+        let mut path = vec![self.root];
+        let mut current = self.root;
+        while current != destination {
+            let (&(_, next), flow) = link_flow
+                .iter_mut()
+                .find(|((from, _), flow)| *from == current && **flow > 0)?;
+            *flow -= 1;
+            if let Some(position) = path.iter().position(|id| *id == next) {
+                path.truncate(position);
+            }
+            path.push(next);
+            current = next;
+        }
+        Some(path)
+    }
+}
@@ -0,0 +1,130 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{Network, NodeSet, RoutingTable, MAX_NODES};
+use std::sync::Arc;
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// A change of the network that the routing table can absorb without a full Dijkstra
+#[derive(Debug, Clone, Copy)]
+pub(super) enum TopologyChange {
+    /// New node without links: no route changes
+    NodeAdded,
+    NodeRemoved(NodeId),
+    /// The cost of entering the node changed (drops or pdr)
+    NodeCostChanged(NodeId),
+    LinkAdded(NodeId, NodeId),
+    LinkRemoved(NodeId, NodeId),
+    LinkCostIncreased(NodeId, NodeId),
+}
+
+impl Network {
+    /// Updates the routing table after `change` (Ramalingam-Reps):
+    /// the subtrees whose route got worse are reset and rebuilt from their boundary,
+    /// then the nodes that got a cheaper route propagate it, the rest of the tree is untouched
+    pub(super) fn apply_change(&mut self, change: TopologyChange) {
+        self.epoch += 1;
+        let Some(mut table) = self.routing_table.get_mut().take() else {
+            return;
+        };
+        let tree = Arc::make_mut(&mut table);
+        let mut reset = NodeSet::new();
+        let mut new_link = None;
+        match change {
+            TopologyChange::NodeAdded => {}
+            TopologyChange::NodeRemoved(id) | TopologyChange::NodeCostChanged(id) => {
+                reset.insert(id);
+            }
+            TopologyChange::LinkAdded(id1, id2) => new_link = Some((id1, id2)),
+            TopologyChange::LinkRemoved(id1, id2) | TopologyChange::LinkCostIncreased(id1, id2) => {
+                for (parent, child) in [(id1, id2), (id2, id1)] {
+                    if tree.reached.contains(child)
+                        && tree.parents[usize::from(child)] == Some(parent)
+                    {
+                        reset.insert(child);
+                    }
+                }
+            }
+        }
+        self.repair(tree, reset, new_link);
+        *self.routing_table.get_mut() = Some(table);
+    }
+    fn repair(&self, tree: &mut RoutingTable, reset: NodeSet, new_link: Option<(NodeId, NodeId)>) {
+        let affected = subtrees(tree, reset);
+        for id in affected {
+            tree.unreach(id);
+        }
+
+        let mut queue = NodeSet::new();
+        // the affected nodes restart from their best neighbour outside the subtrees
+        for v in affected {
+            let Ok(node) = self.get(v) else {
+                continue;
+            };
+            let best = node
+                .neighbours
+                .iter()
+                .filter(|u| tree.reached.contains(*u) && self.expandable(*u))
+                .map(|u| {
+                    (
+                        u,
+                        tree.costs[usize::from(u)].saturating_add(self.link_cost(u, v)),
+                    )
+                })
+                .min_by_key(|(_, cost)| *cost);
+            if let Some((u, cost)) = best {
+                tree.relax(&mut queue, u, v, cost);
+            }
+        }
+        if let Some((id1, id2)) = new_link {
+            for (u, v) in [(id1, id2), (id2, id1)] {
+                if self.contains_id(v) && tree.reached.contains(u) && self.expandable(u) {
+                    let cost = tree.costs[usize::from(u)].saturating_add(self.link_cost(u, v));
+                    tree.relax(&mut queue, u, v, cost);
+                }
+            }
+        }
+
+        while let Some(u) = tree.pop_closest(&mut queue) {
+            if !self.expandable(u) {
+                continue;
+            }
+            let cost = tree.costs[usize::from(u)];
+            for v in self.get(u).map_or(NodeSet::new(), |node| node.neighbours) {
+                if self.contains_id(v) {
+                    tree.relax(&mut queue, u, v, cost.saturating_add(self.link_cost(u, v)));
+                }
+            }
+        }
+    }
+    /// Clients and servers are leaves of the tree, only the root and the drones forward
+    pub(super) fn expandable(&self, id: NodeId) -> bool {
+        id == self.root
+            || self
+                .get(id)
+                .is_ok_and(|node| matches!(node.node_type, NodeType::Drone))
+    }
+}
+
+/// Nodes in the subtrees rooted at `roots` (the tree root is never included)
+fn subtrees(tree: &RoutingTable, roots: NodeSet) -> NodeSet {
+    let mut children = [NodeSet::new(); MAX_NODES];
+    for child in tree.reached {
+        if let Some(parent) = tree.parents[usize::from(child)] {
+            children[usize::from(parent)].insert(child);
+        }
+    }
+    let mut affected = NodeSet::new();
+    let mut pending: NodeSet = roots
+        .into_iter()
+        .filter(|id| *id != tree.root && tree.reached.contains(*id))
+        .collect();
+    while let Some(id) = pending.iter().next() {
+        pending.remove(id);
+        if affected.insert(id) {
+            for child in children[usize::from(id)] {
+                pending.insert(child);
+            }
+        }
+    }
+    affected
+}
@@ -0,0 +1,128 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{dynamic_tree::TopologyChange, LinkConfidence, Network};
+use std::time::{Duration, Instant};
+use wg_2024::network::NodeId;
+
+/// What happens to the links that no `FloodResponse` confirmed recently.
+/// The links of the root are managed with `add_neighbour`/`remove_neighbour`
+/// and never expire
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkExpiry {
+    /// Links live until a crash or a removed neighbour is reported
+    #[default]
+    Never,
+    /// Links not confirmed for `max_age` are removed,
+    /// and so are the nodes left without links
+    Remove { max_age: Duration },
+    /// Links not confirmed for `max_age` cost `penalty` more until they are confirmed again
+    Penalize { max_age: Duration, penalty: u64 },
+}
+
+/// Last confirmation of a link
+#[derive(Debug, Clone, Copy)]
+pub(super) struct LinkState {
+    confirmed: Instant,
+    /// Penalized by `LinkExpiry::Penalize`
+    pub(super) stale: bool,
+    pub(super) confidence: LinkConfidence,
+    /// Highest flood id that confirmed the link
+    pub(super) last_flood: Option<u64>,
+}
+
+impl LinkState {
+    //constructor
+    fn new(confirmed: Instant) -> Self {
+        Self {
+            confirmed,
+            stale: false,
+            confidence: LinkConfidence::default(),
+            last_flood: None,
+        }
+    }
+}
+
+impl Network {
+    //methods
+    pub(super) fn confirm_node(&mut self, id: NodeId, now: Instant) {
+        if self.contains_id(id) {
+            self.node_confirmed[usize::from(id)] = Some(now);
+        }
+    }
+    /// A stale link confirmed again loses its penalty
+    pub(super) fn confirm_link(&mut self, id1: NodeId, id2: NodeId, now: Instant) {
+        let state = self
+            .link_state
+            .get_or_insert_with(id1, id2, || LinkState::new(now));
+        let stale = state.stale;
+        state.confirmed = now;
+        state.stale = false;
+        if stale {
+            self.weight_changed(TopologyChange::LinkAdded(id1, id2));
+        }
+    }
+    /// Ages out the links not confirmed for the `max_age` of the `LinkExpiry`
+    pub fn expire_links(&mut self) {
+        let (max_age, penalty) = match self.expiry {
+            LinkExpiry::Never => return,
+            LinkExpiry::Remove { max_age } => (max_age, None),
+            LinkExpiry::Penalize { max_age, penalty } => (max_age, Some(penalty)),
+        };
+        let (root, now) = (self.root, self.clock.now());
+        let expired = |confirmed: Option<Instant>| {
+            confirmed.is_none_or(|confirmed| now.saturating_duration_since(confirmed) > max_age)
+        };
+        for id1 in (0..=NodeId::MAX).filter(|id| *id != root) {
+            let Ok(node) = self.get(id1) else {
+                continue;
+            };
+            for id2 in node.neighbours {
+                if id2 < id1 || id2 == root {
+                    continue;
+                }
+                let state = self.link_state.get(id1, id2).copied();
+                if !expired(state.map(|state| state.confirmed)) {
+                    continue;
+                }
+                match penalty {
+                    None => {
+                        let _ = self.remove_link(id1, id2);
+                    }
+                    Some(_) if state.is_some_and(|state| state.stale) => {}
+                    Some(_) => {
+                        self.link_state
+                            .get_or_insert_with(id1, id2, || LinkState::new(now))
+                            .stale = true;
+                        self.weight_changed(TopologyChange::LinkCostIncreased(id1, id2));
+                    }
+                }
+            }
+        }
+        if penalty.is_none() {
+            for id in (0..=NodeId::MAX).filter(|id| *id != root) {
+                if self.get(id).is_ok_and(|node| node.neighbours.is_empty())
+                    && expired(self.node_confirmed[usize::from(id)])
+                {
+                    let _ = self.remove_node(id);
+                }
+            }
+        }
+    }
+}
+
+impl Network {
+    //getter/setter
+    /// Last time `id` was seen in a path trace, `None` if it is unknown
+    pub fn last_confirmed(&self, id: NodeId) -> Option<Instant> {
+        self.node_confirmed[usize::from(id)]
+    }
+    /// Last time the link was seen in a path trace or added, `None` if it is unknown
+    pub fn link_last_confirmed(&self, id1: NodeId, id2: NodeId) -> Option<Instant> {
+        let linked = self
+            .get(id1)
+            .is_ok_and(|node| node.neighbours.contains(id2));
+        linked
+            .then(|| self.link_state.get(id1, id2).map(|state| state.confirmed))
+            .flatten()
+    }
+}
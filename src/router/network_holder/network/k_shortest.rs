@@ -0,0 +1,69 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{Network, NodeSet, Path};
+use crate::error::Result;
+use wg_2024::network::NodeId;
+
+impl Network {
+    /// Yen's algorithm: returns at most `k` loopless paths from the root to `destination`,
+    /// sorted by cost (ties are broken by the number of hops).
+    /// As in `get_routes` only the endpoints of a path can be a client or a server.
+    /// # Errors
+    /// - same as [`Network::get_routes`] if the destionation is unreachable
+    pub fn k_shortest_paths(&self, destination: NodeId, k: usize) -> Result<Vec<Path>> {
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+        let first = self.get_routes(destination)?;
+        let mut found = vec![first];
+        let mut candidates: Vec<(u64, Path)> = Vec::new();
+
+        while found.len() < k {
+            let last = found.last().unwrap_or_else(|| unreachable!()).clone();
+            for i in 0..last.len() - 1 {
+                let spur = last[i];
+                let root_path = &last[..=i];
+
+                // links already used by a found path sharing the same root path
+                let banned_links: Vec<(NodeId, NodeId)> = found
+                    .iter()
+                    .filter(|p| p.len() > i + 1 && &p[..=i] == root_path)
+                    .map(|p| (p[i], p[i + 1]))
+                    .collect();
+                let banned_nodes: NodeSet = root_path[..i].iter().copied().collect();
+
+                let Ok(spur_tree) =
+                    self.restricted_dijkstra(spur, Some(destination), banned_nodes, &banned_links)
+                else {
+                    continue;
+                };
+                let Ok(spur_path) = spur_tree.route_to(destination) else {
+                    continue;
+                };
+                let mut path = root_path[..i].to_vec();
+                path.extend(spur_path);
+
+                if !found.contains(&path) && !candidates.iter().any(|(_, p)| *p == path) {
+                    candidates.push((self.path_cost(&path), path));
+                }
+            }
+            if candidates.is_empty() {
+                break;
+            }
+            let best = candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (cost, path))| (*cost, path.len()))
+                .map(|(index, _)| index)
+                .unwrap_or_else(|| unreachable!());
+            found.push(candidates.swap_remove(best).1);
+        }
+        Ok(found)
+    }
+    /// Sum of the costs of the links in `path`
+    pub fn path_cost(&self, path: &[NodeId]) -> u64 {
+        path.windows(2)
+            .map(|w| self.link_cost(w[0], w[1]))
+            .fold(0, u64::saturating_add)
+    }
+}
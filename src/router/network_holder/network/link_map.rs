@@ -0,0 +1,45 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::collections::HashMap;
+use wg_2024::network::NodeId;
+
+/// A value for the links that have one, links being undirected.
+/// Stored sparsely, so that a network only pays for the links it knows
+#[derive(Debug, Clone)]
+pub struct LinkMap<T>(HashMap<(NodeId, NodeId), T>);
+
+impl<T> LinkMap<T> {
+    //constructor
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<T> LinkMap<T> {
+    //methods
+    pub fn get(&self, id1: NodeId, id2: NodeId) -> Option<&T> {
+        self.0.get(&link_key(id1, id2))
+    }
+    pub fn get_mut(&mut self, id1: NodeId, id2: NodeId) -> Option<&mut T> {
+        self.0.get_mut(&link_key(id1, id2))
+    }
+    pub fn get_or_insert_with(
+        &mut self,
+        id1: NodeId,
+        id2: NodeId,
+        default: impl FnOnce() -> T,
+    ) -> &mut T {
+        self.0.entry(link_key(id1, id2)).or_insert_with(default)
+    }
+    pub fn insert(&mut self, id1: NodeId, id2: NodeId, value: T) {
+        self.0.insert(link_key(id1, id2), value);
+    }
+    pub fn remove(&mut self, id1: NodeId, id2: NodeId) -> Option<T> {
+        self.0.remove(&link_key(id1, id2))
+    }
+}
+
+/// Key of the link `(id1, id2)`, the same in both directions
+fn link_key(id1: NodeId, id2: NodeId) -> (NodeId, NodeId) {
+    (id1.min(id2), id1.max(id2))
+}
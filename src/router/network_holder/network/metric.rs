@@ -0,0 +1,150 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::{fmt::Debug, sync::Arc};
+use wg_2024::network::NodeId;
+
+/// Until real delivery counters are available the drops on a link are compared
+/// with this number of (assumed) delivered packets to estimate its pdr
+pub const PDR_PRIOR_DELIVERED: f64 = 10.0;
+
+/// What a [`RouteMetric`] knows about the link it has to price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkStats {
+    pub from: NodeId,
+    pub to: NodeId,
+    /// Number of packets dropped on the link plus the ones dropped by `to`, decayed with age
+    pub drops: f64,
+    /// Estimated probability that a packet crossing the link is dropped
+    pub pdr: f64,
+    /// Number of distinct floods that confirmed the link
+    pub floods: u32,
+}
+
+/// Cost of a link used by Dijkstra, the cost of a path is the sum of the costs of its links
+pub trait RouteMetric: Debug + Send + Sync {
+    fn cost(&self, link: &LinkStats) -> u64;
+    /// Whether `cost` reads `LinkStats::pdr`, when it does not the new pdr estimates
+    /// leave the routes (and the topology epoch) as they are
+    fn uses_pdr(&self) -> bool {
+        true
+    }
+}
+
+/// Every link costs 1, the cheapest path is the one with less hops
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HopCount;
+
+impl RouteMetric for HopCount {
+    fn cost(&self, _link: &LinkStats) -> u64 {
+        1
+    }
+    fn uses_pdr(&self) -> bool {
+        false
+    }
+}
+
+/// A link costs the number of packets dropped on it (the default metric)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DropCount;
+
+impl RouteMetric for DropCount {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn cost(&self, link: &LinkStats) -> u64 {
+        link.drops.round() as u64
+    }
+    fn uses_pdr(&self) -> bool {
+        false
+    }
+}
+
+/// A link costs `-ln(1 - pdr) * scale`: summing the costs along a path is the same as
+/// multiplying the delivery probabilities, so the cheapest path is the most reliable one
+#[derive(Debug, Clone, Copy)]
+pub struct DeliveryProbability {
+    scale: f64,
+}
+
+impl DeliveryProbability {
+    //constructor
+    #[must_use]
+    pub fn new(scale: f64) -> Self {
+        Self { scale }
+    }
+}
+
+impl Default for DeliveryProbability {
+    fn default() -> Self {
+        Self::new(1000.0)
+    }
+}
+
+impl RouteMetric for DeliveryProbability {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn cost(&self, link: &LinkStats) -> u64 {
+        let delivery = (1.0 - link.pdr).clamp(f64::EPSILON, 1.0);
+        (-delivery.ln() * self.scale).round() as u64
+    }
+}
+
+/// A link costs `scale / floods`, where `floods` is the number of distinct floods that
+/// confirmed it: links seen in a single odd path trace lose to links confirmed many times.
+/// Meant to be combined with other metrics in a `WeightedMetric`
+#[derive(Debug, Clone, Copy)]
+pub struct Confidence {
+    scale: f64,
+}
+
+impl Confidence {
+    //constructor
+    #[must_use]
+    pub fn new(scale: f64) -> Self {
+        Self { scale }
+    }
+}
+
+impl Default for Confidence {
+    fn default() -> Self {
+        Self::new(10.0)
+    }
+}
+
+impl RouteMetric for Confidence {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn cost(&self, link: &LinkStats) -> u64 {
+        (self.scale / f64::from(link.floods.max(1))).round() as u64
+    }
+    fn uses_pdr(&self) -> bool {
+        false
+    }
+}
+
+/// Weighted sum of other metrics, e.g. `WeightedMetric::new().with(1, HopCount).with(2, DropCount)`
+#[derive(Debug, Clone, Default)]
+pub struct WeightedMetric {
+    parts: Vec<(u64, Arc<dyn RouteMetric>)>,
+}
+
+impl WeightedMetric {
+    //constructor
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    #[must_use]
+    pub fn with(mut self, weight: u64, metric: impl RouteMetric + 'static) -> Self {
+        self.parts.push((weight, Arc::new(metric)));
+        self
+    }
+}
+
+impl RouteMetric for WeightedMetric {
+    fn cost(&self, link: &LinkStats) -> u64 {
+        self.parts
+            .iter()
+            .map(|(weight, metric)| weight.saturating_mul(metric.cost(link)))
+            .fold(0, u64::saturating_add)
+    }
+    fn uses_pdr(&self) -> bool {
+        self.parts.iter().any(|(_, metric)| metric.uses_pdr())
+    }
+}
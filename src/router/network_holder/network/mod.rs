@@ -2,43 +2,142 @@
 
 use crate::error::{
     Result,
-    RouterError::{IdAlreadyPresent, IdNotFound, ParentsMalformed, RemoveSelfErr, RouteNotFound},
+    RouterError::{IdAlreadyPresent, IdNotFound, InvalidPathTrace, RemoveSelfErr, RouteNotFound},
 };
+use dynamic_tree::TopologyChange;
+use expiry::LinkState;
+use link_map::LinkMap;
 use log::info;
 use network_node::NetworkNode;
-use priority_queue::PriorityQueue;
+use node_set::NodeSet;
 use std::{
-    cell::RefCell,
-    cmp::Reverse,
-    collections::{HashMap, HashSet, VecDeque},
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use wg_2024::{
+    network::NodeId,
+    packet::{FloodResponse, NodeType},
 };
-use wg_2024::{network::NodeId, packet::NodeType};
 
 pub type Path = Vec<NodeId>;
 
+/// `NodeId` is a `u8`, so a network has at most 256 nodes
+const MAX_NODES: usize = NodeId::MAX as usize + 1;
+
+pub use analysis::{ArticulationPoint, Bridge, TopologyAnalysis};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use confidence::LinkConfidence;
+pub use constrained::RouteConstraints;
+pub use disjoint::Disjointness;
+pub use expiry::LinkExpiry;
+pub use metric::{
+    Confidence, DeliveryProbability, DropCount, HopCount, LinkStats, RouteMetric, WeightedMetric,
+    PDR_PRIOR_DELIVERED,
+};
+pub use partition::Partition;
+pub use path_trace::PathTraceValidator;
+pub use reconcile::TypeConflict;
+pub use routing_table::RoutingTable;
+use tombstone::Tombstone;
+pub use tombstone::TOMBSTONE_LIFETIME;
+use weight::DecayingWeight;
+pub use weight::{WeightDecay, DECAY_REFRESHES_PER_HALF_LIFE};
+
+mod analysis;
+mod clock;
+mod confidence;
+mod constrained;
+mod disjoint;
+mod dynamic_tree;
+mod expiry;
+mod k_shortest;
+mod link_map;
+mod metric;
 mod network_node;
+mod node_set;
+mod partition;
+mod path_trace;
+mod reconcile;
+mod routing_table;
 #[cfg(test)]
 mod test;
+mod tombstone;
+mod weight;
 
 #[allow(clippy::struct_field_names)]
 #[derive(Debug, Clone)]
 pub struct Network {
     root: NodeId,
-    network: HashMap<NodeId, NetworkNode>,
-    weight: RefCell<HashMap<(NodeId, NodeId), u64>>,
-    server_list: HashSet<NodeId>,
+    /// `nodes[id]` is the node `id`, if it is in the network
+    nodes: [Option<NetworkNode>; MAX_NODES],
+    /// Drops charged to a node, paid once by every path crossing it
+    node_weight: [Option<DecayingWeight>; MAX_NODES],
+    /// Drops charged to a link
+    weight: LinkMap<DecayingWeight>,
+    decay: WeightDecay,
+    /// Number of drops recorded, used by `WeightDecay::Observations`
+    observations: u64,
+    server_list: NodeSet,
+    metric: Arc<dyn RouteMetric>,
+    /// Estimated packet drop rate of the drones, see `PdrEstimator`
+    pdr: [Option<f64>; MAX_NODES],
+    /// Built on demand, then repaired at every change of the topology or of the weights
+    routing_table: RefCell<Option<Arc<RoutingTable>>>,
+    /// Decay refreshes (see `WeightDecay::refresh_interval`) when the routing table was built
+    table_refreshes: Cell<u64>,
+    /// Incremented at every change of the topology or of the weights
+    epoch: u64,
+    /// Incremented when a node or a link is added or removed, or a node changes type,
+    /// not when the costs change
+    structure_epoch: u64,
+    /// Start of the decay refreshes counted by `epoch`
+    decay_start: Instant,
+    clock: Arc<dyn Clock>,
+    expiry: LinkExpiry,
+    /// Last time a node was seen in a path trace (or added)
+    node_confirmed: [Option<Instant>; MAX_NODES],
+    /// Last time a link was seen in a path trace (or added)
+    link_state: LinkMap<LinkState>,
+    /// Crashed nodes, see `bury_node`
+    node_tombstones: [Option<Tombstone>; MAX_NODES],
+    /// Removed links, see `bury_link`
+    link_tombstones: LinkMap<Tombstone>,
+    tombstone_lifetime: Duration,
 }
 
 impl Network {
     //constructor
+    #[cfg(test)]
     pub fn new(root: NodeId, root_type: NodeType) -> Self {
-        let mut network = HashMap::new();
-        network.insert(root, NetworkNode::new(root_type));
+        Self::with_metric(root, root_type, Arc::new(DropCount))
+    }
+    pub fn with_metric(root: NodeId, root_type: NodeType, metric: Arc<dyn RouteMetric>) -> Self {
+        let mut nodes = [None; MAX_NODES];
+        nodes[usize::from(root)] = Some(NetworkNode::new(root_type));
         Self {
             root,
-            network,
-            weight: HashMap::new().into(),
-            server_list: HashSet::new(),
+            nodes,
+            node_weight: [None; MAX_NODES],
+            weight: LinkMap::new(),
+            decay: WeightDecay::default(),
+            observations: 0,
+            server_list: NodeSet::new(),
+            metric,
+            pdr: [None; MAX_NODES],
+            routing_table: RefCell::new(None),
+            table_refreshes: Cell::new(0),
+            epoch: 0,
+            structure_epoch: 0,
+            decay_start: Instant::now(),
+            clock: Arc::new(SystemClock),
+            expiry: LinkExpiry::default(),
+            node_confirmed: [None; MAX_NODES],
+            link_state: LinkMap::new(),
+            node_tombstones: [None; MAX_NODES],
+            link_tombstones: LinkMap::new(),
+            tombstone_lifetime: TOMBSTONE_LIFETIME,
         }
     }
 }
@@ -46,32 +145,53 @@ impl Network {
 impl Network {
     //methods
     pub fn contains_id(&self, key: NodeId) -> bool {
-        self.network.contains_key(&key)
+        self.nodes[usize::from(key)].is_some()
     }
-    pub fn update_from_path_trace(&mut self, path_trace: &[(NodeId, NodeType)]) {
-        if let Some((first_id, first_type)) = path_trace.first() {
-            if !self.contains_id(*first_id) {
-                let _ = self.add_empty_node(*first_id, *first_type);
+    /// # Errors
+    /// See `update_from_path_trace`
+    pub fn update_from_flood_response(
+        &mut self,
+        response: &FloodResponse,
+        validator: &PathTraceValidator,
+    ) -> Result<Vec<TypeConflict>> {
+        self.update_from_path_trace(&response.path_trace, Some(response.flood_id), validator)
+    }
+    /// The trace is checked (and repaired if allowed) by `validator` and cut
+    /// at the buried nodes and links (see `bury_node`), then its links are counted
+    /// as confirmed by `flood_id`, see `LinkConfidence`.
+    /// The types in the trace replace the known ones
+    /// # Errors
+    /// - `Ok(conflicts)` : the known nodes whose type changed
+    /// - `Err(InvalidPathTrace)` if the trace is rejected, nothing is learned
+    pub fn update_from_path_trace(
+        &mut self,
+        path_trace: &[(NodeId, NodeType)],
+        flood_id: Option<u64>,
+        validator: &PathTraceValidator,
+    ) -> Result<Vec<TypeConflict>> {
+        let path_trace = validator
+            .validate(path_trace)
+            .map_err(|error| Box::new(InvalidPathTrace(error)))?;
+        let path_trace = self.cut_at_tombstones(path_trace, flood_id);
+        let now = self.clock.now();
+        let mut conflicts = Vec::new();
+        for (id, node_type) in &path_trace {
+            if self.contains_id(*id) {
+                conflicts.extend(self.reconcile_type(*id, *node_type, flood_id));
+            } else {
+                let _ = self.add_empty_node(*id, *node_type);
             }
-            let _ = self.add_link(self.root, *first_id);
         }
-        for i in 0..path_trace.len() - 1 {
-            let (id1, type1) = path_trace[i];
-            if let NodeType::Server = type1 {
-                self.server_list.insert(id1);
-            }
-            let (id2, type2) = path_trace[i + 1];
-            if let NodeType::Server = type2 {
-                self.server_list.insert(id2);
-            }
-            if !self.contains_id(id1) {
-                let _ = self.add_empty_node(id1, type1);
-            }
-            if !self.contains_id(id2) {
-                let _ = self.add_empty_node(id2, type2);
-            }
+        for pair in path_trace.windows(2) {
+            let (id1, id2) = (pair[0].0, pair[1].0);
             let _ = self.add_link(id1, id2);
+            self.confirm_link(id1, id2, now);
+        }
+        for (id, _) in &path_trace {
+            self.confirm_node(*id, now);
         }
+        self.observe_links(&path_trace, flood_id);
+        Ok(conflicts)
     }
     /// Remove the node specified from the network
     /// # Errors
@@ -79,154 +199,233 @@ impl Network {
     /// - `Err(IdNotFound)`
     /// - `Ok(id)` id the node deleted
     pub fn remove_node(&mut self, id: NodeId) -> Result<NodeId> {
+        self.forget_node(id)?;
+        self.log_network();
+        Ok(id)
+    }
+    /// `remove_node` without logging the network
+    fn forget_node(&mut self, id: NodeId) -> Result<NodeId> {
         if self.root == id {
             return Err(Box::new(RemoveSelfErr));
         }
         if self.get(id)?.node_type == NodeType::Server {
-            self.server_list.remove(&id);
+            self.server_list.remove(id);
         }
 
-        self.network.remove(&id).ok_or(IdNotFound(id))?;
-        for v in self.network.values_mut() {
+        self.nodes[usize::from(id)] = None;
+        self.node_confirmed[usize::from(id)] = None;
+        for v in self.nodes.iter_mut().flatten() {
             v.remove_neighbour(id);
         }
-        self.log_network();
+        self.structure_epoch += 1;
+        self.apply_change(TopologyChange::NodeRemoved(id));
         Ok(id)
     }
+    /// A neighbour not in the network yet is guessed to be a drone,
+    /// until a path trace tells its type
     /// # Errors
-    /// - `IdAlreadyPresent`
+    /// - `Err(IdAlreadyPresent)` if `id` is the root
     pub fn add_neighbour(&mut self, id: NodeId) -> Result<()> {
-        self.add_empty_node(id, NodeType::Drone)?;
-        let _ = self.add_link(id, self.root);
-        Ok(())
+        if id == self.root {
+            return Err(Box::new(IdAlreadyPresent {
+                id,
+                node_type: self.get(id)?.node_type,
+            }));
+        }
+        self.unbury_neighbour(id);
+        if self.add_empty_node(id, NodeType::Drone).is_ok() {
+            self.get_mut(id)?.guessed = true;
+        }
+        self.add_link(id, self.root)
     }
     /// # Errors
     /// - `IdAlreadyPresent`
     pub fn remove_neighbour_link(&mut self, id: NodeId) -> Result<()> {
-        self.get(self.root)?.remove_neighbour(id);
-        self.get(id)?.remove_neighbour(self.root);
-
-        Ok(())
+        self.remove_link(self.root, id)
     }
-    /// Increment weight of every link directed to `id`
+    /// Charge a drop to the node `id`, every path crossing `id` pays it once
     /// # Errors
     /// - `IdNotFound`
     pub fn increment_weight(&mut self, id: NodeId) -> Result<()> {
-        for neighbour in self.get(id)?.neighbours.borrow().iter() {
-            self.weight
-                .borrow_mut()
-                .entry((id, *neighbour))
-                .and_modify(|w| *w += 1)
-                .or_insert(0);
-            self.weight
-                .borrow_mut()
-                .entry((*neighbour, id))
-                .and_modify(|w| *w += 1)
-                .or_insert(0);
+        self.get(id)?;
+        add_drop(
+            &mut self.node_weight[usize::from(id)],
+            self.decay,
+            self.clock.now(),
+            self.observations,
+        );
+        self.observations += 1;
+        self.weight_changed(TopologyChange::NodeCostChanged(id));
+        Ok(())
+    }
+    /// Charge a drop to the link `(id1, id2)`, when it is known which link
+    /// the packet was forwarded over
+    /// # Errors
+    /// - `IdNotFound`
+    pub fn increment_link_weight(&mut self, id1: NodeId, id2: NodeId) -> Result<()> {
+        self.get(id1)?;
+        self.get(id2)?;
+        let now = self.clock.now();
+        match self.weight.get_mut(id1, id2) {
+            Some(weight) => weight.add(1.0, self.decay, now, self.observations),
+            None => self
+                .weight
+                .insert(id1, id2, DecayingWeight::new(1.0, now, self.observations)),
         }
+        self.observations += 1;
+        self.weight_changed(TopologyChange::LinkCostIncreased(id1, id2));
         Ok(())
     }
-    /// Compute vector of parent of the network starting from the root
+    /// Shortest path tree of the whole network, built once per topology change
+    /// and rebuilt with the decayed weights at every decay refresh
     /// # Errors
-    /// - `Ok(HashMap<u,v>)` : `v` is the father of `u`
     /// - `Err(IdNotFound)` : if the network refer to a node no longer in the network
-    #[allow(dead_code)]
-    fn bfs(&self) -> Result<HashMap<NodeId, Option<NodeId>>> {
-        let mut queue = VecDeque::new();
-        queue.push_back(self.root);
-
-        let mut visited = HashSet::new();
-        visited.insert(self.root);
-
-        let mut parents = HashMap::new();
-        parents.insert(self.root, None);
-
-        while !queue.is_empty() {
-            let u = queue.pop_front().unwrap_or_else(|| unreachable!());
-            for &v in self.get(u)?.neighbours.borrow().iter() {
-                if !visited.contains(&v) {
-                    parents.insert(v, Some(u));
-                    visited.insert(v);
-                    queue.push_back(v);
-                }
-            }
+    pub fn routing_table(&self) -> Result<Arc<RoutingTable>> {
+        let refreshes = self.decay_refreshes();
+        if self.table_refreshes.replace(refreshes) != refreshes {
+            *self.routing_table.borrow_mut() = None;
         }
-        Ok(parents)
+        if let Some(table) = self.routing_table.borrow().as_ref() {
+            return Ok(table.clone());
+        }
+        let table = Arc::new(self.restricted_dijkstra(self.root, None, NodeSet::new(), &[])?);
+        *self.routing_table.borrow_mut() = Some(table.clone());
+        Ok(table)
     }
-    fn dijkstra(&self, destination: NodeId) -> Result<HashMap<NodeId, Option<NodeId>>> {
-        // let mut queue = BinaryHeap::new();
-        // queue.push(Reverse(PriorityItem::new(0, self.root)));
-        let mut queue = PriorityQueue::new();
-        queue.push(self.root, Reverse(0u64));
-
-        let mut inside_queue = HashSet::new();
-        inside_queue.insert(self.root);
-
-        let mut distance = HashMap::new();
-        distance.insert(self.root, 0u64);
-
-        let mut parents = HashMap::new();
-        parents.insert(self.root, None);
+    /// Number of `WeightDecay::refresh_interval` elapsed since `decay_start`
+    fn decay_refreshes(&self) -> u64 {
+        self.decay.refresh_interval().map_or(0, |interval| {
+            let elapsed = self.clock.now().saturating_duration_since(self.decay_start);
+            u64::try_from(elapsed.as_nanos() / interval.as_nanos()).unwrap_or(u64::MAX)
+        })
+    }
+    /// Counts the decay refreshes elapsed in `epoch` and starts counting them again,
+    /// before the clock or the decay change
+    fn restart_decay_refreshes(&mut self) {
+        self.epoch = self.epoch.saturating_add(self.decay_refreshes());
+        self.decay_start = self.clock.now();
+    }
+    /// Drops the routing table, it will be rebuilt from scratch
+    fn topology_changed(&mut self) {
+        *self.routing_table.get_mut() = None;
+        self.epoch += 1;
+    }
+    /// With a decay every weight changes when a drop is recorded,
+    /// so the routing table can be repaired only if drops are never forgotten
+    fn weight_changed(&mut self, change: TopologyChange) {
+        if self.decay == WeightDecay::None {
+            self.apply_change(change);
+        } else {
+            self.topology_changed();
+        }
+    }
+    /// Dijkstra starting from `source`, ignoring the nodes in `banned_nodes` and the
+    /// links in `banned_links` (links are undirected, so `(u, v)` also bans `(v, u)`).
+    /// Clients and servers are never crossed: only `destination` is reached, or all of them
+    /// as leaves of the tree if `destination` is `None`.
+    /// Nothing is allocated, the queue being a `NodeSet`
+    /// # Errors
+    /// - `Ok(tree)` : the shortest path tree rooted at `source`
+    /// - `Err(IdNotFound)` : if `source` is not in the network
+    fn restricted_dijkstra(
+        &self,
+        source: NodeId,
+        destination: Option<NodeId>,
+        banned_nodes: NodeSet,
+        banned_links: &[(NodeId, NodeId)],
+    ) -> Result<RoutingTable> {
+        self.get(source)?;
+        let mut tree = RoutingTable::new(source);
+        let mut queue = NodeSet::new();
+        queue.insert(source);
 
-        while !queue.is_empty() {
-            let (u, _) = queue.pop().unwrap_or_else(|| unreachable!());
-            inside_queue.remove(&u);
-            for &v in self.get(u)?.neighbours.borrow().iter().filter_map(|n| {
-                // if *n == destination {
-                //     Some(n)
-                // } else {
-                //     self.get(*n)
-                //         .ok()
-                //         .filter(|node| matches!(node.node_type, NodeType::Drone))
-                //         .map(|_| n)
-                // } ;
-                match self.get(*n).ok()?.node_type {
-                    NodeType::Drone => Some(n),
-                    _ if destination == *n => Some(n),
-                    _ => None,
+        while let Some(u) = tree.pop_closest(&mut queue) {
+            let node = self.get(u)?;
+            if u != source && !matches!(node.node_type, NodeType::Drone) {
+                continue;
+            }
+            let cost = tree.costs[usize::from(u)];
+            for v in node.neighbours {
+                if banned_nodes.contains(v)
+                    || banned_links.contains(&(u, v))
+                    || banned_links.contains(&(v, u))
+                {
+                    continue;
                 }
-            }) {
-                let new_distance = distance.get(&u).unwrap_or(&u64::MAX) + self.get_weight(u, v);
-                if new_distance < *distance.get(&v).unwrap_or(&u64::MAX) {
-                    if inside_queue.contains(&v) {
-                        queue.change_priority(&v, std::cmp::Reverse(new_distance));
-                    } else {
-                        queue.push(v, std::cmp::Reverse(new_distance));
-                        inside_queue.insert(v);
-                    }
-                    parents.insert(v, Some(u));
-                    distance.insert(v, new_distance);
+                let Ok(neighbour) = self.get(v) else {
+                    continue;
+                };
+                match neighbour.node_type {
+                    NodeType::Drone => {}
+                    _ if destination.is_none_or(|d| d == v) => {}
+                    _ => continue,
                 }
+                tree.relax(&mut queue, u, v, cost.saturating_add(self.link_cost(u, v)));
             }
         }
-        Ok(parents)
-    }
-    fn get_weight(&self, id1: NodeId, id2: NodeId) -> u64 {
-        let weight = self
-            .weight
-            .borrow()
-            .get(&(id1, id2))
-            .or(self.weight.borrow().get(&(id2, id1)))
-            .copied()
-            .unwrap_or(0);
-        if weight > 400 {
-            if let Ok(mut wfield) = self.weight.try_borrow_mut() {
-                *wfield = HashMap::new();
+        Ok(tree)
+    }
+    /// Cost of the link `(id1, id2)` according to the metric of the network,
+    /// plus the penalty of `LinkExpiry::Penalize` if the link is stale
+    fn link_cost(&self, id1: NodeId, id2: NodeId) -> u64 {
+        let penalty = match self.expiry {
+            LinkExpiry::Penalize { penalty, .. }
+                if self
+                    .link_state
+                    .get(id1, id2)
+                    .is_some_and(|state| state.stale) =>
+            {
+                penalty
             }
+            _ => 0,
+        };
+        self.metric
+            .cost(&self.link_stats(id1, id2))
+            .saturating_add(penalty)
+    }
+    /// The pdr of a link is the estimated pdr of the drone it leads to,
+    /// if it has never been estimated it is inferred from the drops paid on the link
+    fn link_stats(&self, id1: NodeId, id2: NodeId) -> LinkStats {
+        let drops = self.get_weight(id1, id2);
+        let confidence = self
+            .link_state
+            .get(id1, id2)
+            .map(|state| state.confidence)
+            .unwrap_or_default();
+        LinkStats {
+            from: id1,
+            to: id2,
+            drops,
+            floods: confidence.floods,
+            pdr: self.pdr[usize::from(id2)]
+                .unwrap_or_else(|| drops / (drops + PDR_PRIOR_DELIVERED)),
         }
-        weight
+    }
+    /// Drops paid going from `id1` to `id2`: the ones on the link plus the ones of `id2`,
+    /// decayed according to `self.decay`
+    fn get_weight(&self, id1: NodeId, id2: NodeId) -> f64 {
+        let now = self.clock.now();
+        let value = |w: &DecayingWeight| w.value(self.decay, now, self.observations);
+        let link = self.weight.get(id1, id2).map_or(0.0, value);
+        let node = self.node_weight[usize::from(id2)]
+            .as_ref()
+            .map_or(0.0, value);
+        link + node
     }
     /// Add a node without neighbours to the network
     /// # Errors
     /// - `Err(IdAlreadyPresent)` if the id is already in the network
     fn add_empty_node(&mut self, id: NodeId, node_type: NodeType) -> Result<()> {
-        if self.network.contains_key(&id) {
+        if self.contains_id(id) {
             return Err(Box::new(IdAlreadyPresent { id, node_type }));
         }
         if node_type == NodeType::Server {
             self.server_list.insert(id);
         }
-        self.network.insert(id, NetworkNode::new(node_type));
+        self.nodes[usize::from(id)] = Some(NetworkNode::new(node_type));
+        self.node_confirmed[usize::from(id)] = Some(self.clock.now());
+        self.structure_epoch += 1;
+        self.apply_change(TopologyChange::NodeAdded);
         Ok(())
     }
     /// Add `(id1, id2)` and `(id2, id1)` because link are undirected
@@ -236,57 +435,26 @@ impl Network {
         if !self.contains_id(id2) {
             return Err(Box::new(IdNotFound(id2)));
         }
-        self.network
-            .get_mut(&id1)
-            .ok_or(IdNotFound(id1))?
-            .add_neighbour(id2);
-        self.network
-            .get_mut(&id2)
-            .ok_or(IdNotFound(id2))?
-            .add_neighbour(id1);
+        let added = self.get_mut(id1)?.add_neighbour(id2);
+        let added = self.get_mut(id2)?.add_neighbour(id1) || added;
+        if added {
+            self.confirm_link(id1, id2, self.clock.now());
+            self.structure_epoch += 1;
+            self.apply_change(TopologyChange::LinkAdded(id1, id2));
+        }
         Ok(())
     }
-    /// Compute all the paths between the root and a destination
-    ///
-    fn dfs(
-        &self,
-        current: u8,
-        destination: u8,
-        visited: &mut HashSet<u8>,
-        current_path: &mut Vec<u8>,
-        paths: &mut Vec<Vec<u8>>,
-    ) {
-        if current == destination {
-            paths.push(current_path.clone());
-            return;
-        }
-
-        visited.insert(current);
-
-        if let Some(current_node) = self.network.get(&current) {
-            for &neighbor in current_node.neighbours.borrow().iter() {
-                if visited.contains(&neighbor) {
-                    continue;
-                }
-
-                let Some(neighbor_node) = self.network.get(&neighbor) else {
-                    continue;
-                };
-
-                let should_visit = match neighbor_node.node_type {
-                    NodeType::Drone => true,
-                    _ => neighbor == destination,
-                };
-
-                if should_visit {
-                    current_path.push(neighbor);
-                    self.dfs(neighbor, destination, visited, current_path, paths);
-                    current_path.pop();
-                }
-            }
-        }
-
-        visited.remove(&current);
+    /// Removes `(id1, id2)` and `(id2, id1)`, as when a `Nack` of type `ErrorInRouting`
+    /// reports the link missing
+    /// # Errors
+    /// - `Err(IdNotFound)` if one of ithe ids is not in the network
+    pub(crate) fn remove_link(&mut self, id1: NodeId, id2: NodeId) -> Result<()> {
+        self.get_mut(id1)?.remove_neighbour(id2);
+        self.get_mut(id2)?.remove_neighbour(id1);
+        self.link_state.remove(id1, id2);
+        self.structure_epoch += 1;
+        self.apply_change(TopologyChange::LinkRemoved(id1, id2));
+        Ok(())
     }
 }
 
@@ -295,70 +463,102 @@ impl Network {
     /// # Errors:
     /// - `Err(IdNotFound)`
     pub fn get(&self, id: NodeId) -> Result<&NetworkNode> {
-        self.network.get(&id).ok_or(Box::new(IdNotFound(id)))
+        self.nodes[usize::from(id)]
+            .as_ref()
+            .ok_or(Box::new(IdNotFound(id)))
+    }
+    fn get_mut(&mut self, id: NodeId) -> Result<&mut NetworkNode> {
+        self.nodes[usize::from(id)]
+            .as_mut()
+            .ok_or(Box::new(IdNotFound(id)))
+    }
+    /// Every node in the network, in ascending order of id
+    fn iter_nodes(&self) -> impl Iterator<Item = (NodeId, &NetworkNode)> {
+        (0..=NodeId::MAX)
+            .zip(&self.nodes)
+            .filter_map(|(id, node)| Some((id, node.as_ref()?)))
     }
     /// # Errors
     /// - `Err(RouteNotFound)` if the destionation is unreachable
     /// - `Err(ParentsMalformed)` if the vector of parents is malformed
     pub fn get_routes(&self, destination: NodeId) -> Result<Path> {
-        // let parents = self.bfs().or(Err(RouteNotFound { destination }))?;
         // let path = parents_to_path(&parents, destination)?;
-        let parents = self
-            .dijkstra(destination)
-            .or(Err(RouteNotFound { destination }))?;
-        let path = parents_to_path(&parents, destination)?;
-        Ok(path)
+        self.routing_table()
+            .or(Err(RouteNotFound { destination }))?
+            .route_to(destination)
     }
     pub fn get_server_list(&self) -> HashSet<NodeId> {
-        self.server_list.clone()
-    }
-    /// Useful when a drone in a path has a high pdr,
-    /// the client/server can call this method and decide which path
-    /// the dropped packet will be sent through.
-    pub fn multiple_paths(&self, destination_id: NodeId) -> Vec<Path> {
-        let mut paths = Vec::new();
-        let mut visited = HashSet::new();
-        let mut current_path = vec![self.root];
-        self.dfs(
-            self.root,
-            destination_id,
-            &mut visited,
-            &mut current_path,
-            &mut paths,
-        );
-        paths
+        self.server_list.into_iter().collect()
+    }
+    /// Starts from the drops recorded by `previous` (the generation learned before),
+    /// so that a new generation still knows how reliable the nodes and links were.
+    /// To be called before recording any drop
+    pub fn inherit_weights(&mut self, previous: &Network) {
+        self.node_weight = previous.node_weight;
+        self.weight = previous.weight.clone();
+        self.observations = previous.observations;
+    }
+    pub fn set_weight_decay(&mut self, decay: WeightDecay) {
+        self.restart_decay_refreshes();
+        self.decay = decay;
+        self.topology_changed();
+    }
+    /// The clock used for the timestamps of the topology and the decay of the weights
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.restart_decay_refreshes();
+        self.clock = clock;
+        self.decay_start = self.clock.now();
+        self.topology_changed();
+    }
+    pub fn set_link_expiry(&mut self, expiry: LinkExpiry) {
+        self.expiry = expiry;
+        self.topology_changed();
+    }
+    pub fn set_pdr(&mut self, id: NodeId, pdr: f64) {
+        self.pdr[usize::from(id)] = Some(pdr);
+        if self.metric.uses_pdr() {
+            self.apply_change(TopologyChange::NodeCostChanged(id));
+        }
     }
     pub fn log_network(&self) {
         let mut info_str = "Path trace \n\n".to_string();
-        for (node_id, node) in &self.network {
+        for (node_id, node) in self.iter_nodes() {
             info_str.push_str(format!("\n\t {node_id} => ").as_str());
-            let iter = node.neighbours.borrow();
-            for v in iter.iter() {
+            for v in node.neighbours {
                 info_str.push_str(format!(" {v}").as_str());
             }
         }
         info!("[RouterOf: {}] == {info_str}", self.root);
-        println!("[RouterOf: {}] == {info_str}", self.root);
+    }
+    /// Changes every time the topology or the weights change,
+    /// also when the weights decay with time (see `WeightDecay::refresh_interval`)
+    pub fn epoch(&self) -> u64 {
+        self.epoch.saturating_add(self.decay_refreshes())
+    }
+    /// Changes when a node or a link is added or removed, or a node changes type
+    pub fn structure_epoch(&self) -> u64 {
+        self.structure_epoch
     }
     pub fn get_node_number(&self) -> usize {
-        self.network.keys().len()
+        self.nodes.iter().flatten().count()
+    }
+    pub fn get_link_number(&self) -> usize {
+        self.iter_nodes()
+            .map(|(_, node)| node.neighbours.len())
+            .sum::<usize>()
+            / 2
     }
 }
 
-/// Returns a path from the vector of parents
-/// # Errors
-/// - `Err(ParentsMalformed)` if the vector of parents is malformed
-fn parents_to_path(parents: &HashMap<NodeId, Option<NodeId>>, destination: NodeId) -> Result<Path> {
-    let mut path = vec![destination];
-    let mut current = &destination;
-    while let Some(parent) = parents.get(current).ok_or(ParentsMalformed {
-        parents: parents.clone(),
-        destination,
-    })? {
-        path.push(*parent);
-        current = parent;
-    }
-    path.reverse();
-    Ok(path)
+/// Adds a drop to `weight`, decaying the ones already there
+fn add_drop(
+    weight: &mut Option<DecayingWeight>,
+    decay: WeightDecay,
+    now: Instant,
+    observation: u64,
+) {
+    match weight {
+        Some(value) => value.add(1.0, decay, now, observation),
+        None => *weight = Some(DecayingWeight::new(1.0, now, observation)),
+    }
 }
-
@@ -4,21 +4,28 @@ use wg_2024::packet::NodeType;
 
 use wg_2024::network::NodeId;
 
-use std::cell::RefCell;
+use super::node_set::NodeSet;
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct NetworkNode {
-    pub(crate) neighbours: RefCell<Vec<NodeId>>,
+    pub(crate) neighbours: NodeSet,
     pub(crate) node_type: NodeType,
+    /// `node_type` has not been seen in a path trace yet
+    pub(crate) guessed: bool,
+    /// Flood from which `node_type` is known, the path traces of the older floods
+    /// do not change it. `None` if it is not known from any flood
+    pub(crate) type_flood: Option<u64>,
 }
 
 impl NetworkNode {
     //constructor
     pub(crate) fn new(node_type: NodeType) -> Self {
         Self {
-            neighbours: RefCell::new(Vec::new()),
+            neighbours: NodeSet::new(),
             node_type,
+            guessed: false,
+            type_flood: None,
         }
     }
 }
@@ -27,29 +34,13 @@ impl NetworkNode {
     //methods
     /// # Note
     /// Does not check if the id is valid, so you have to ensure that the id is already in the network
-    pub(crate) fn add_neighbour(&self, id: NodeId) {
-        if !self.neighbours.borrow().contains(&id) {
-            self.neighbours.borrow_mut().push(id);
-        }
+    /// # Returns
+    /// `false` if `id` was already a neighbour
+    pub(crate) fn add_neighbour(&mut self, id: NodeId) -> bool {
+        self.neighbours.insert(id)
     }
-    /// # Note
-    /// Does not preserve order in the vector
-    pub(crate) fn remove_neighbour(&self, id: NodeId) {
-        let index = self.neighbours.borrow().iter().position(|&i| i == id);
-        if let Some(index) = index {
-            if let Ok(mut a) = self.neighbours.try_borrow_mut() {
-                a.swap_remove(index);
-            }
-        }
+    pub(crate) fn remove_neighbour(&mut self, id: NodeId) {
+        self.neighbours.remove(id);
     }
-    /* /// Add some ids to the neightbours calling `std::vec::reserve()` before
-    /// # Note
-    /// Does not check if the ids are valid, so you have to ensure that the ids are already in the network
-    fn bulk_add_neighbours(&self, ids: Vec<NodeId>) {
-        self.neighbours.borrow_mut().reserve(ids.len());
-        for id in ids {
-            self.add_neighbour(id);
-        }
-    } */
 }
 
@@ -0,0 +1,112 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::fmt;
+use wg_2024::network::NodeId;
+
+/// Set of `NodeId` stored as a 256 bit bitset, `NodeId` being a `u8`
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct NodeSet([u64; 4]);
+
+impl NodeSet {
+    //constructor
+    pub const fn new() -> Self {
+        Self([0; 4])
+    }
+}
+
+impl NodeSet {
+    //methods
+    /// # Returns
+    /// `false` if `id` was already in the set
+    pub fn insert(&mut self, id: NodeId) -> bool {
+        let (word, bit) = position(id);
+        let absent = self.0[word] & bit == 0;
+        self.0[word] |= bit;
+        absent
+    }
+    /// # Returns
+    /// `false` if `id` was not in the set
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        let (word, bit) = position(id);
+        let present = self.0[word] & bit != 0;
+        self.0[word] &= !bit;
+        present
+    }
+    pub fn contains(&self, id: NodeId) -> bool {
+        let (word, bit) = position(id);
+        self.0[word] & bit != 0
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+    /// Iterates the ids in ascending order
+    pub fn iter(&self) -> Iter {
+        Iter {
+            words: self.0,
+            word: 0,
+        }
+    }
+}
+
+fn position(id: NodeId) -> (usize, u64) {
+    (usize::from(id / 64), 1 << (id % 64))
+}
+
+impl fmt::Debug for NodeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<NodeId> for NodeSet {
+    fn from_iter<T: IntoIterator<Item = NodeId>>(iter: T) -> Self {
+        let mut set = Self::new();
+        for id in iter {
+            set.insert(id);
+        }
+        set
+    }
+}
+
+impl Extend<NodeId> for NodeSet {
+    fn extend<T: IntoIterator<Item = NodeId>>(&mut self, iter: T) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl IntoIterator for NodeSet {
+    type Item = NodeId;
+    type IntoIter = Iter;
+    fn into_iter(self) -> Iter {
+        self.iter()
+    }
+}
+
+/// Iterator over a copy of the set, so the set can be modified while iterating
+#[derive(Debug, Clone)]
+pub struct Iter {
+    words: [u64; 4],
+    word: usize,
+}
+
+impl Iterator for Iter {
+    type Item = NodeId;
+    #[allow(clippy::cast_possible_truncation)]
+    fn next(&mut self) -> Option<NodeId> {
+        while self.word < self.words.len() {
+            let bits = self.words[self.word];
+            if bits == 0 {
+                self.word += 1;
+                continue;
+            }
+            self.words[self.word] &= bits - 1;
+            return Some((self.word * 64 + bits.trailing_zeros() as usize) as NodeId);
+        }
+        None
+    }
+}
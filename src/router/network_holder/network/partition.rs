@@ -0,0 +1,91 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{Network, NodeSet};
+use std::collections::HashSet;
+use wg_2024::network::NodeId;
+
+/// Split of the known nodes between the ones the root can route to
+/// and the components cut off from it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Partition {
+    /// Nodes with a route from the root (the root included)
+    pub reachable: HashSet<NodeId>,
+    /// Connected components of the nodes without a route, sorted by their smallest id
+    pub orphaned: Vec<HashSet<NodeId>>,
+}
+
+impl Partition {
+    //getter/setter
+    #[must_use]
+    pub fn is_partitioned(&self) -> bool {
+        !self.orphaned.is_empty()
+    }
+    #[must_use]
+    pub fn orphaned_nodes(&self) -> HashSet<NodeId> {
+        self.orphaned.iter().flatten().copied().collect()
+    }
+}
+
+impl Network {
+    /// Splits the network in the part reachable from the root and the orphaned components.
+    /// A node is reachable when it has a route, so a node linked to the rest
+    /// only through clients or servers is orphaned
+    pub fn partition(&self) -> Partition {
+        let reachable = self.reachable();
+        let mut orphaned = Vec::new();
+        let mut left: NodeSet = self
+            .iter_nodes()
+            .map(|(id, _)| id)
+            .filter(|id| !reachable.contains(*id))
+            .collect();
+        while let Some(start) = left.iter().next() {
+            let mut component = NodeSet::new();
+            let mut stack = vec![start];
+            left.remove(start);
+            while let Some(id) = stack.pop() {
+                component.insert(id);
+                for neighbour in self.get(id).map_or(NodeSet::new(), |node| node.neighbours) {
+                    if left.remove(neighbour) {
+                        stack.push(neighbour);
+                    }
+                }
+            }
+            orphaned.push(component.into_iter().collect());
+        }
+        Partition {
+            reachable: reachable.into_iter().collect(),
+            orphaned,
+        }
+    }
+    /// Removes the nodes without a route from the root
+    /// # Returns
+    /// The removed nodes
+    pub fn prune_orphans(&mut self) -> Vec<NodeId> {
+        let reachable = self.reachable();
+        let orphans: Vec<NodeId> = self
+            .iter_nodes()
+            .map(|(id, _)| id)
+            .filter(|id| !reachable.contains(*id))
+            .collect();
+        for id in &orphans {
+            let _ = self.forget_node(*id);
+        }
+        if !orphans.is_empty() {
+            self.log_network();
+        }
+        orphans
+    }
+    /// Servers with a route from the root
+    pub fn get_reachable_server_list(&self) -> HashSet<NodeId> {
+        let reachable = self.reachable();
+        self.server_list
+            .iter()
+            .filter(|id| reachable.contains(*id))
+            .collect()
+    }
+    fn reachable(&self) -> NodeSet {
+        self.routing_table()
+            .map(|table| table.reached)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,84 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::node_set::NodeSet;
+use crate::error::PathTraceError::{self, Empty, LeafForwarded, NotFromRoot, Repeated, SelfLoop};
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// Checks a path trace before it is learned: it has to start at the root, never
+/// cross a node twice and have clients and servers only at its ends.
+/// When repairing, a node repeated in a row is kept once and the trace is cut where
+/// it turns invalid; a trace that does not start at the root is never repaired
+#[derive(Debug, Clone, Copy)]
+pub struct PathTraceValidator {
+    root: NodeId,
+    repair: bool,
+}
+
+impl PathTraceValidator {
+    //constructor
+    /// Strict validator, see `with_repair`
+    #[must_use]
+    pub fn new(root: NodeId) -> Self {
+        Self {
+            root,
+            repair: false,
+        }
+    }
+    #[must_use]
+    pub fn with_repair(mut self, repair: bool) -> Self {
+        self.repair = repair;
+        self
+    }
+}
+
+impl PathTraceValidator {
+    //methods
+    /// # Errors
+    /// - `Ok(trace)` : the trace to learn, starting at the root
+    /// - `Err(Empty)`, `Err(NotFromRoot)` : even when repairing
+    /// - `Err(_)` : the first problem found, only when not repairing
+    pub fn validate(
+        &self,
+        path_trace: &[(NodeId, NodeType)],
+    ) -> Result<Vec<(NodeId, NodeType)>, PathTraceError> {
+        let Some((first, _)) = path_trace.first() else {
+            return Err(Empty);
+        };
+        if *first != self.root {
+            return Err(NotFromRoot { first: *first });
+        }
+        let mut trace = Vec::with_capacity(path_trace.len());
+        let mut crossed = NodeSet::new();
+        for (id, node_type) in path_trace {
+            let error = match trace.last() {
+                Some((last, _)) if last == id => Some(SelfLoop(*id)),
+                // the root is where the trace starts, whatever its type
+                Some((last, last_type)) if trace.len() > 1 && *last_type != NodeType::Drone => {
+                    Some(LeafForwarded {
+                        id: *last,
+                        node_type: *last_type,
+                    })
+                }
+                _ if crossed.contains(*id) => Some(Repeated(*id)),
+                _ => None,
+            };
+            match error {
+                None => {
+                    crossed.insert(*id);
+                    trace.push((*id, *node_type));
+                }
+                Some(error) if !self.repair => return Err(error),
+                Some(SelfLoop(_)) => {}
+                Some(_) => break,
+            }
+        }
+        Ok(trace)
+    }
+}
+
+impl PathTraceValidator {
+    //getter/setter
+    pub fn repairs(&self) -> bool {
+        self.repair
+    }
+}
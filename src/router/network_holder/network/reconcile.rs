@@ -0,0 +1,83 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::Network;
+use crate::error::Result;
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// A node seen in a path trace with a type different from the known one,
+/// see `Router::type_conflicts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeConflict {
+    pub id: NodeId,
+    pub previous: NodeType,
+    /// The type now used for the node
+    pub observed: NodeType,
+    /// The previous type was only guessed, as for the neighbours added with `add_neighbour`
+    pub guessed: bool,
+    pub flood_id: Option<u64>,
+}
+
+impl Network {
+    //methods
+    /// The type observed in a path trace of the flood `flood_id` (or reported by a
+    /// `Nack` before that flood) is authoritative and replaces the known one,
+    /// unless the known one comes from a newer flood.
+    /// Only the type of the root never changes
+    /// # Returns
+    /// The conflict if the type changed
+    pub fn reconcile_type(
+        &mut self,
+        id: NodeId,
+        observed: NodeType,
+        flood_id: Option<u64>,
+    ) -> Option<TypeConflict> {
+        if id == self.root {
+            return None;
+        }
+        let node = self.get_mut(id).ok()?;
+        if flood_id < node.type_flood {
+            return None;
+        }
+        let (previous, guessed) = (node.node_type, node.guessed);
+        node.guessed = false;
+        node.type_flood = flood_id;
+        if previous == observed {
+            return None;
+        }
+        let _ = self.set_node_type(id, observed);
+        Some(TypeConflict {
+            id,
+            previous,
+            observed,
+            guessed,
+            flood_id,
+        })
+    }
+}
+
+impl Network {
+    //getter/setter
+    /// Changes the type of `id`, keeping the server list consistent.
+    /// Clients and servers do not forward packets, so the routes are recomputed
+    /// # Errors
+    /// - `Err(IdNotFound)`
+    pub fn set_node_type(&mut self, id: NodeId, node_type: NodeType) -> Result<()> {
+        let node = self.get_mut(id)?;
+        if node.node_type == node_type {
+            return Ok(());
+        }
+        node.node_type = node_type;
+        if node_type == NodeType::Server {
+            self.server_list.insert(id);
+        } else {
+            self.server_list.remove(id);
+        }
+        self.structure_epoch += 1;
+        self.topology_changed();
+        Ok(())
+    }
+    /// `None` if `id` is not in the network
+    pub fn node_type(&self, id: NodeId) -> Option<NodeType> {
+        self.get(id).ok().map(|node| node.node_type)
+    }
+}
@@ -0,0 +1,120 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::{node_set::NodeSet, Path, MAX_NODES};
+use crate::error::{
+    Result,
+    RouterError::{ParentsMalformed, RouteNotFound},
+};
+use std::collections::HashMap;
+use wg_2024::network::NodeId;
+
+/// Shortest path tree from the root, built with a single Dijkstra
+/// and kept up to date while the topology of the network changes
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone)]
+pub struct RoutingTable {
+    pub(super) root: NodeId,
+    pub(super) reached: NodeSet,
+    /// `parents[u]` is the father of `u`, meaningful only if `u` is reached
+    pub(super) parents: [Option<NodeId>; MAX_NODES],
+    /// `costs[u]` is the cost of the route to `u`, meaningful only if `u` is reached
+    pub(super) costs: [u64; MAX_NODES],
+}
+
+impl RoutingTable {
+    //constructor
+    /// Tree where only `root` is reached
+    pub(super) fn new(root: NodeId) -> Self {
+        let mut reached = NodeSet::new();
+        reached.insert(root);
+        let mut costs = [u64::MAX; MAX_NODES];
+        costs[usize::from(root)] = 0;
+        Self {
+            root,
+            reached,
+            parents: [None; MAX_NODES],
+            costs,
+        }
+    }
+}
+
+impl RoutingTable {
+    //methods
+    /// Sets `parent` as the father of `id` if `cost` is better than the current one,
+    /// and puts `id` in the `queue`
+    pub(super) fn relax(&mut self, queue: &mut NodeSet, parent: NodeId, id: NodeId, cost: u64) {
+        let index = usize::from(id);
+        if !self.reached.contains(id) || cost < self.costs[index] {
+            self.reached.insert(id);
+            self.parents[index] = Some(parent);
+            self.costs[index] = cost;
+            queue.insert(id);
+        }
+    }
+    /// Removes from `queue` and returns its cheapest node (the lowest id on ties)
+    pub(super) fn pop_closest(&self, queue: &mut NodeSet) -> Option<NodeId> {
+        let closest = queue.iter().min_by_key(|id| self.costs[usize::from(*id)])?;
+        queue.remove(closest);
+        Some(closest)
+    }
+    pub(super) fn unreach(&mut self, id: NodeId) {
+        self.reached.remove(id);
+        self.parents[usize::from(id)] = None;
+        self.costs[usize::from(id)] = u64::MAX;
+    }
+}
+
+impl RoutingTable {
+    //getter/setter
+    /// Path from the root to `destination`, computed in O(path length)
+    /// # Errors
+    /// - `Err(RouteNotFound)` if the destionation is unreachable
+    /// - `Err(ParentsMalformed)` if the tree is malformed
+    pub fn route_to(&self, destination: NodeId) -> Result<Path> {
+        if !self.reached.contains(destination) {
+            return Err(Box::new(RouteNotFound { destination }));
+        }
+        let mut path = vec![destination];
+        let mut current = destination;
+        while let Some(parent) = self.parents[usize::from(current)] {
+            if path.len() > MAX_NODES || !self.reached.contains(parent) {
+                return Err(self.malformed(destination));
+            }
+            path.push(parent);
+            current = parent;
+        }
+        if current != self.root {
+            return Err(self.malformed(destination));
+        }
+        path.reverse();
+        Ok(path)
+    }
+    /// Cost of the route to `destination`, `None` if it is unreachable
+    #[must_use]
+    pub fn cost(&self, destination: NodeId) -> Option<u64> {
+        self.reached
+            .contains(destination)
+            .then(|| self.costs[usize::from(destination)])
+    }
+    /// Every reachable node (the root excluded) with the cost of its route
+    pub fn destinations(&self) -> impl Iterator<Item = (NodeId, u64)> + '_ {
+        self.reached
+            .iter()
+            .filter(|id| *id != self.root)
+            .map(|id| (id, self.costs[usize::from(id)]))
+    }
+    #[must_use]
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+    fn malformed(&self, destination: NodeId) -> Box<crate::error::RouterError> {
+        Box::new(ParentsMalformed {
+            parents: self
+                .reached
+                .iter()
+                .map(|id| (id, self.parents[usize::from(id)]))
+                .collect::<HashMap<_, _>>(),
+            destination,
+        })
+    }
+}
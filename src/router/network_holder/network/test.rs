@@ -2,6 +2,10 @@
 
 use super::*;
 
+fn validator() -> PathTraceValidator {
+    PathTraceValidator::new(1).with_repair(true)
+}
+
 #[test]
 fn test_bfs() {
     let mut network = Network::new(1, NodeType::Client);
@@ -54,13 +58,794 @@ fn test_djikstra() {
 
 #[test]
 fn test_remove_neighbour() {
-    let node = NetworkNode::new(NodeType::Drone);
-    node.add_neighbour(1);
-    node.add_neighbour(2);
-    node.add_neighbour(2);
+    let mut node = NetworkNode::new(NodeType::Drone);
+    assert!(node.add_neighbour(1));
+    assert!(node.add_neighbour(2));
+    assert!(!node.add_neighbour(2));
     node.add_neighbour(3);
 
     node.remove_neighbour(12);
     node.add_neighbour(2);
 }
 
+#[test]
+fn test_k_shortest_paths() {
+    let mut network = Network::new(1, NodeType::Client);
+    let _ = network.add_empty_node(2, NodeType::Drone);
+    let _ = network.add_empty_node(3, NodeType::Drone);
+    let _ = network.add_empty_node(4, NodeType::Drone);
+    let _ = network.add_empty_node(5, NodeType::Client);
+    let _ = network.add_empty_node(6, NodeType::Server);
+    // 1 => 2 => 6
+    // 1 => 3 => 4 => 6
+    // 2 => 3
+    // 2 => 5 => 4 (5 is a client, can not be crossed)
+    let _ = network.add_link(1, 2);
+    let _ = network.add_link(2, 6);
+    let _ = network.add_link(1, 3);
+    let _ = network.add_link(3, 4);
+    let _ = network.add_link(4, 6);
+    let _ = network.add_link(2, 3);
+    let _ = network.add_link(2, 5);
+    let _ = network.add_link(5, 4);
+
+    let _ = network.increment_weight(2);
+    let _ = network.increment_weight(2);
+
+    let paths = network.k_shortest_paths(6, 10).unwrap();
+    assert_eq!(paths[0], vec![1, 3, 4, 6]);
+    assert_eq!(paths.len(), 4);
+    assert!(paths.iter().all(|p| !p.contains(&5)));
+    let costs: Vec<u64> = paths.iter().map(|p| network.path_cost(p)).collect();
+    assert!(costs.windows(2).all(|c| c[0] <= c[1]));
+
+    assert_eq!(network.k_shortest_paths(6, 2).unwrap().len(), 2);
+    assert!(network.k_shortest_paths(6, 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_disjoint_paths() {
+    let mut network = Network::new(1, NodeType::Client);
+    for id in 2..=7 {
+        let _ = network.add_empty_node(id, NodeType::Drone);
+    }
+    let _ = network.add_empty_node(8, NodeType::Server);
+    // 1 => 2 => 3 => 8
+    // 1 => 4 => 3
+    // 2 => 5 => 8
+    // 1 => 6 => 7 => 3
+    let _ = network.add_link(1, 2);
+    let _ = network.add_link(2, 3);
+    let _ = network.add_link(3, 8);
+    let _ = network.add_link(1, 4);
+    let _ = network.add_link(4, 3);
+    let _ = network.add_link(2, 5);
+    let _ = network.add_link(5, 8);
+    let _ = network.add_link(1, 6);
+    let _ = network.add_link(6, 7);
+    let _ = network.add_link(7, 3);
+
+    // every path to 8 goes through 2 or 3
+    let paths = network.disjoint_paths(8, 3, Disjointness::Node);
+    assert_eq!(paths.len(), 2);
+    let intermediates: Vec<NodeId> = paths
+        .iter()
+        .flat_map(|p| p[1..p.len() - 1].to_vec())
+        .collect();
+    let unique: HashSet<NodeId> = intermediates.iter().copied().collect();
+    assert_eq!(intermediates.len(), unique.len());
+    for path in &paths {
+        assert_eq!(path.first(), Some(&1));
+        assert_eq!(path.last(), Some(&8));
+    }
+
+    // only two links reach 8
+    let paths = network.disjoint_paths(8, 3, Disjointness::Link);
+    assert_eq!(paths.len(), 2);
+    let links: HashSet<(NodeId, NodeId)> = paths
+        .iter()
+        .flat_map(|p| {
+            p.windows(2)
+                .map(|w| (w[0].min(w[1]), w[0].max(w[1])))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let link_count: usize = paths.iter().map(|p| p.len() - 1).sum();
+    assert_eq!(links.len(), link_count);
+
+    // 3 => 8 crashed: only 2 => 5 => 8 is left
+    let _ = network.remove_node(3);
+    assert_eq!(
+        network.disjoint_paths(8, 2, Disjointness::Node),
+        vec![vec![1, 2, 5, 8]]
+    );
+}
+
+#[test]
+fn test_route_metric() {
+    let build = |metric: Arc<dyn RouteMetric>| {
+        let mut network = Network::with_metric(1, NodeType::Client, metric);
+        for id in 2..=5 {
+            let _ = network.add_empty_node(id, NodeType::Drone);
+        }
+        let _ = network.add_empty_node(6, NodeType::Server);
+        // 1 => 2 => 6
+        // 1 => 3 => 4 => 5 => 6
+        let _ = network.add_link(1, 2);
+        let _ = network.add_link(2, 6);
+        let _ = network.add_link(1, 3);
+        let _ = network.add_link(3, 4);
+        let _ = network.add_link(4, 5);
+        let _ = network.add_link(5, 6);
+        for _ in 0..5 {
+            let _ = network.increment_weight(2);
+        }
+        network
+    };
+
+    let network = build(Arc::new(DropCount));
+    assert_eq!(network.get_routes(6).unwrap(), vec![1, 3, 4, 5, 6]);
+    let network = build(Arc::new(DeliveryProbability::default()));
+    assert_eq!(network.get_routes(6).unwrap(), vec![1, 3, 4, 5, 6]);
+    let network = build(Arc::new(HopCount));
+    assert_eq!(network.get_routes(6).unwrap(), vec![1, 2, 6]);
+
+    let network = build(Arc::new(
+        WeightedMetric::new().with(100, HopCount).with(1, DropCount),
+    ));
+    assert_eq!(network.get_routes(6).unwrap(), vec![1, 2, 6]);
+    let network = build(Arc::new(
+        WeightedMetric::new().with(1, HopCount).with(1, DropCount),
+    ));
+    assert_eq!(network.get_routes(6).unwrap(), vec![1, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_weight_decay() {
+    let mut network = Network::new(1, NodeType::Client);
+    network.set_weight_decay(WeightDecay::Observations { half_life: 2 });
+    let _ = network.add_empty_node(2, NodeType::Drone);
+    let _ = network.add_empty_node(3, NodeType::Drone);
+    let _ = network.add_link(1, 2);
+    let _ = network.add_link(1, 3);
+
+    for _ in 0..5 {
+        let _ = network.increment_weight(2);
+    }
+    let before = network.get_weight(1, 2);
+    assert!(before > 0.0);
+    // reading does not change the weight
+    assert!((network.get_weight(1, 2) - before).abs() < f64::EPSILON);
+
+    let _ = network.increment_weight(3);
+    let _ = network.increment_weight(3);
+    assert!((network.get_weight(1, 2) - before / 2.0).abs() < 1e-9);
+
+    network.set_weight_decay(WeightDecay::None);
+    let stored = network.get_weight(1, 2);
+    let _ = network.increment_weight(3);
+    assert!((network.get_weight(1, 2) - stored).abs() < f64::EPSILON);
+}
+
+#[test]
+fn test_node_drop_attribution() {
+    let mut network = Network::new(1, NodeType::Client);
+    network.set_weight_decay(WeightDecay::None);
+    for id in 2..=4 {
+        let _ = network.add_empty_node(id, NodeType::Drone);
+    }
+    let _ = network.add_empty_node(5, NodeType::Server);
+    // 1 => 2 => 3 => 5
+    // 2 => 4 => 5
+    let _ = network.add_link(1, 2);
+    let _ = network.add_link(2, 3);
+    let _ = network.add_link(3, 5);
+    let _ = network.add_link(2, 4);
+    let _ = network.add_link(4, 5);
+
+    // the first drop counts
+    let _ = network.increment_weight(3);
+    assert!((network.get_weight(2, 3) - 1.0).abs() < f64::EPSILON);
+    // the drop is paid entering 3, not leaving it or on the links of its neighbours
+    assert!(network.get_weight(3, 5).abs() < f64::EPSILON);
+    assert!(network.get_weight(1, 2).abs() < f64::EPSILON);
+    assert_eq!(network.path_cost(&[1, 2, 3, 5]), 1);
+    assert_eq!(network.path_cost(&[1, 2, 4, 5]), 0);
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 2, 4, 5]);
+
+    let _ = network.increment_link_weight(4, 5);
+    let _ = network.increment_link_weight(5, 4);
+    assert_eq!(network.path_cost(&[1, 2, 4, 5]), 2);
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 2, 3, 5]);
+}
+
+#[test]
+fn test_routing_table() {
+    let mut network = Network::new(1, NodeType::Client);
+    network.set_weight_decay(WeightDecay::None);
+    for id in 2..=4 {
+        let _ = network.add_empty_node(id, NodeType::Drone);
+    }
+    let _ = network.add_empty_node(5, NodeType::Server);
+    let _ = network.add_empty_node(6, NodeType::Client);
+    // 1 => 2 => 3 => 5
+    // 1 => 4 => 5 => 6 (5 is a server, can not be crossed)
+    // 3 => 6
+    let _ = network.add_link(1, 2);
+    let _ = network.add_link(2, 3);
+    let _ = network.add_link(3, 5);
+    let _ = network.add_link(1, 4);
+    let _ = network.add_link(4, 5);
+    let _ = network.add_link(5, 6);
+    let _ = network.add_link(3, 6);
+    let _ = network.increment_weight(4);
+
+    let table = network.routing_table().unwrap();
+    assert_eq!(table.route_to(5).unwrap(), vec![1, 2, 3, 5]);
+    assert_eq!(table.route_to(6).unwrap(), vec![1, 2, 3, 6]);
+    assert_eq!(table.cost(4), Some(1));
+    let mut destinations: Vec<NodeId> = table.destinations().map(|(id, _)| id).collect();
+    destinations.sort_unstable();
+    assert_eq!(destinations, vec![2, 3, 4, 5, 6]);
+    for (destination, cost) in table.destinations() {
+        let path = network.get_routes(destination).unwrap();
+        assert_eq!(network.path_cost(&path), cost);
+    }
+
+    // the table is rebuilt only when the topology changes
+    assert!(Arc::ptr_eq(&table, &network.routing_table().unwrap()));
+    let _ = network.remove_node(3);
+    let table = network.routing_table().unwrap();
+    assert_eq!(table.route_to(5).unwrap(), vec![1, 4, 5]);
+    assert!(table.route_to(6).is_err());
+    assert!(table.route_to(3).is_err());
+}
+
+#[test]
+fn test_incremental_routing_table() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    for seed in 0..20 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let metric = WeightedMetric::new().with(1, HopCount).with(3, DropCount);
+        let mut network = Network::with_metric(0, NodeType::Client, Arc::new(metric));
+        network.set_weight_decay(WeightDecay::None);
+        for id in 1..30 {
+            let node_type = match rng.random_range(0..10) {
+                0 => NodeType::Server,
+                1 => NodeType::Client,
+                _ => NodeType::Drone,
+            };
+            let _ = network.add_empty_node(id, node_type);
+        }
+        for _ in 0..60 {
+            let _ = network.add_link(rng.random_range(0..30), rng.random_range(1..30));
+        }
+        let _ = network.routing_table();
+
+        for _ in 0..100 {
+            let id = rng.random_range(1..30);
+            let other = rng.random_range(0..30);
+            match rng.random_range(0..7) {
+                0 => {
+                    let _ = network.remove_node(id);
+                }
+                1 => {
+                    let _ = network.add_empty_node(id, NodeType::Drone);
+                    let _ = network.add_link(id, other);
+                }
+                2 => {
+                    let _ = network.add_link(id, other);
+                }
+                3 => {
+                    let _ = network.remove_neighbour_link(id);
+                }
+                4 => {
+                    let _ = network.increment_link_weight(id, other);
+                }
+                5 => network.set_pdr(id, rng.random_range(0.0..1.0)),
+                _ => {
+                    let _ = network.increment_weight(id);
+                }
+            }
+
+            let table = network.routing_table().unwrap();
+            let expected = network
+                .restricted_dijkstra(0, None, NodeSet::new(), &[])
+                .unwrap();
+            assert_eq!(
+                table.destinations().collect::<Vec<_>>(),
+                expected.destinations().collect::<Vec<_>>(),
+                "seed {seed}"
+            );
+            for (destination, cost) in table.destinations() {
+                let path = table.route_to(destination).unwrap();
+                assert_eq!(network.path_cost(&path), cost, "seed {seed}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_constrained_path() {
+    let mut network = Network::new(1, NodeType::Client);
+    for id in 2..=6 {
+        let _ = network.add_empty_node(id, NodeType::Drone);
+    }
+    let _ = network.add_empty_node(7, NodeType::Client);
+    let _ = network.add_empty_node(8, NodeType::Server);
+    // 1 => 2 => 8
+    // 1 => 3 => 4 => 8
+    // 2 => 5 => 6 => 8
+    // 2 => 7 => 4 (7 is a client, can not be crossed)
+    for (id1, id2) in [
+        (1, 2),
+        (2, 8),
+        (1, 3),
+        (3, 4),
+        (4, 8),
+        (2, 5),
+        (5, 6),
+        (6, 8),
+    ] {
+        let _ = network.add_link(id1, id2);
+    }
+    let _ = network.add_link(2, 7);
+    let _ = network.add_link(7, 4);
+
+    let none = RouteConstraints::new();
+    assert_eq!(network.constrained_path(8, &none).unwrap(), vec![1, 2, 8]);
+
+    let avoid = RouteConstraints::new().avoiding(2);
+    assert_eq!(
+        network.constrained_path(8, &avoid).unwrap(),
+        vec![1, 3, 4, 8]
+    );
+
+    let through = RouteConstraints::new().through(5);
+    assert_eq!(
+        network.constrained_path(8, &through).unwrap(),
+        vec![1, 2, 5, 6, 8]
+    );
+    let short = through.clone().with_max_hops(3);
+    assert!(network.constrained_path(8, &short).is_err());
+    let exact = through.with_max_hops(4);
+    assert_eq!(
+        network.constrained_path(8, &exact).unwrap(),
+        vec![1, 2, 5, 6, 8]
+    );
+
+    // the cheapest path is too long, a more expensive one fits
+    let _ = network.increment_weight(2);
+    let _ = network.increment_weight(2);
+    assert_eq!(
+        network.constrained_path(8, &none).unwrap(),
+        vec![1, 3, 4, 8]
+    );
+    let two_hops = RouteConstraints::new().with_max_hops(2);
+    assert_eq!(
+        network.constrained_path(8, &two_hops).unwrap(),
+        vec![1, 2, 8]
+    );
+
+    // waypoints are crossed in order, without going back on the same drones
+    let ordered = RouteConstraints::new().through(3).through(4);
+    assert_eq!(
+        network.constrained_path(8, &ordered).unwrap(),
+        vec![1, 3, 4, 8]
+    );
+    let reversed = RouteConstraints::new().through(4).through(3);
+    assert!(network.constrained_path(8, &reversed).is_err());
+    // clients are never waypoints, unknown ids are reported
+    assert!(network
+        .constrained_path(8, &RouteConstraints::new().through(7))
+        .is_err());
+    assert!(network
+        .constrained_path(8, &RouteConstraints::new().through(42))
+        .is_err());
+    assert!(network
+        .constrained_path(8, &RouteConstraints::new().avoiding(8))
+        .is_err());
+}
+
+#[test]
+fn test_topology_analysis() {
+    let mut network = Network::new(1, NodeType::Client);
+    for id in 2..=5 {
+        let _ = network.add_empty_node(id, NodeType::Drone);
+    }
+    let _ = network.add_empty_node(8, NodeType::Server);
+    let _ = network.add_empty_node(9, NodeType::Server);
+    let _ = network.add_empty_node(10, NodeType::Client);
+    // 1 => 2 => 3 => 8
+    // 2 => 4 => 8, 3 => 4
+    // 2 => 5 => 9
+    // 3 => 10 => 5 (10 is a client, it does not connect 3 and 5)
+    for (id1, id2) in [
+        (1, 2),
+        (2, 3),
+        (3, 8),
+        (2, 4),
+        (4, 8),
+        (3, 4),
+        (2, 5),
+        (5, 9),
+    ] {
+        let _ = network.add_link(id1, id2);
+    }
+    let _ = network.add_link(3, 10);
+    let _ = network.add_link(10, 5);
+
+    let analysis = network.analyze_topology();
+    let points: Vec<(NodeId, HashSet<NodeId>)> = analysis
+        .articulation_points
+        .iter()
+        .map(|point| (point.id, point.unreachable.clone()))
+        .collect();
+    assert_eq!(
+        points,
+        vec![
+            (2, HashSet::from([3, 4, 5, 8, 9, 10])),
+            (5, HashSet::from([9]))
+        ]
+    );
+    let bridges: Vec<(NodeId, NodeId)> = analysis.bridges.iter().map(|b| b.link).collect();
+    assert_eq!(bridges, vec![(1, 2), (2, 5), (5, 9)]);
+    assert_eq!(analysis.bridges[1].unreachable, HashSet::from([5, 9]));
+    assert_eq!(analysis.single_points_of_failure(9), vec![2, 5]);
+    assert_eq!(analysis.single_points_of_failure(8), vec![2]);
+
+    // a second path to 5 removes it from the single points of failure
+    let _ = network.add_link(4, 5);
+    let analysis = network.analyze_topology();
+    assert_eq!(analysis.single_points_of_failure(10), vec![2]);
+    assert!(!analysis.bridges.iter().any(|b| b.link == (2, 5)));
+}
+
+#[test]
+fn test_link_expiry() {
+    use std::time::Duration;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut network = Network::new(1, NodeType::Client);
+    network.set_clock(clock.clone());
+    // 1 => 2 => 3 => 5
+    // 1 => 4 => 3
+    let long = [
+        (1, NodeType::Client),
+        (2, NodeType::Drone),
+        (3, NodeType::Drone),
+        (5, NodeType::Server),
+    ];
+    let short = [
+        (1, NodeType::Client),
+        (4, NodeType::Drone),
+        (3, NodeType::Drone),
+    ];
+    network
+        .update_from_path_trace(&long, None, &validator())
+        .unwrap();
+    network
+        .update_from_path_trace(&short, None, &validator())
+        .unwrap();
+    let _ = network.increment_weight(4);
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 2, 3, 5]);
+    let seen = network.link_last_confirmed(2, 3).unwrap();
+
+    // only 1 => 4 => 3 => 5 is confirmed again
+    clock.advance(Duration::from_secs(10));
+    network
+        .update_from_path_trace(
+            &[
+                (1, NodeType::Client),
+                (4, NodeType::Drone),
+                (3, NodeType::Drone),
+                (5, NodeType::Server),
+            ],
+            None,
+            &validator(),
+        )
+        .unwrap();
+    assert_eq!(network.link_last_confirmed(2, 3), Some(seen));
+    assert!(network.last_confirmed(3).unwrap() > seen);
+
+    network.set_link_expiry(LinkExpiry::Penalize {
+        max_age: Duration::from_secs(5),
+        penalty: 10,
+    });
+    network.expire_links();
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 4, 3, 5]);
+    // confirmed again, the penalty is gone
+    network
+        .update_from_path_trace(&long, None, &validator())
+        .unwrap();
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 2, 3, 5]);
+
+    clock.advance(Duration::from_secs(10));
+    network
+        .update_from_path_trace(&short, None, &validator())
+        .unwrap();
+    network.set_link_expiry(LinkExpiry::Remove {
+        max_age: Duration::from_secs(5),
+    });
+    network.expire_links();
+    // the links of the root never expire
+    assert!(network.get(1).unwrap().neighbours.contains(2));
+    assert!(!network.get(2).unwrap().neighbours.contains(3));
+    assert!(!network.contains_id(5));
+    assert!(network.get(4).unwrap().neighbours.contains(3));
+}
+
+#[test]
+fn test_link_confidence() {
+    let response = |flood_id, path_trace: &[NodeId]| FloodResponse {
+        flood_id,
+        path_trace: path_trace
+            .iter()
+            .map(|id| {
+                let node_type = match id {
+                    1 => NodeType::Client,
+                    9 => NodeType::Server,
+                    _ => NodeType::Drone,
+                };
+                (*id, node_type)
+            })
+            .collect(),
+    };
+    let build = |metric: Arc<dyn RouteMetric>| {
+        let mut network = Network::with_metric(1, NodeType::Client, metric);
+        // 1 => 2 => 3 => 9 confirmed by three floods
+        // 1 => 4 => 9 seen once
+        for flood_id in 1..=3 {
+            network
+                .update_from_flood_response(&response(flood_id, &[1, 2, 3, 9]), &validator())
+                .unwrap();
+        }
+        network
+            .update_from_flood_response(&response(3, &[1, 2, 3, 9]), &validator())
+            .unwrap();
+        network
+            .update_from_flood_response(&response(3, &[1, 4, 9]), &validator())
+            .unwrap();
+        network
+    };
+
+    let network = build(Arc::new(
+        WeightedMetric::new()
+            .with(1, HopCount)
+            .with(1, Confidence::default()),
+    ));
+    assert_eq!(
+        network.link_confidence(3, 9),
+        Some(LinkConfidence {
+            responses: 4,
+            floods: 3
+        })
+    );
+    assert_eq!(
+        network.link_confidence(1, 4),
+        Some(LinkConfidence {
+            responses: 1,
+            floods: 1
+        })
+    );
+    assert_eq!(network.link_confidence(2, 4), None);
+    assert_eq!(network.get_routes(9).unwrap(), vec![1, 2, 3, 9]);
+
+    // without confidence the shorter route wins
+    let network = build(Arc::new(HopCount));
+    assert_eq!(network.get_routes(9).unwrap(), vec![1, 4, 9]);
+}
+
+#[test]
+fn test_path_trace_validator() {
+    use crate::error::PathTraceError;
+
+    let (d, s) = (NodeType::Drone, NodeType::Server);
+    let strict = PathTraceValidator::new(1);
+    let repair = strict.with_repair(true);
+    let error = |trace: &[(NodeId, NodeType)]| strict.validate(trace).unwrap_err();
+    let ids = |trace: &[(NodeId, NodeType)]| -> Vec<NodeId> {
+        repair
+            .validate(trace)
+            .unwrap()
+            .iter()
+            .map(|(id, _)| *id)
+            .collect()
+    };
+    let client = (1, NodeType::Client);
+
+    assert_eq!(error(&[]), PathTraceError::Empty);
+    assert!(repair.validate(&[]).is_err());
+    assert_eq!(
+        error(&[(2, d), (3, s)]),
+        PathTraceError::NotFromRoot { first: 2 }
+    );
+    // the root is never added in front of a trace
+    assert_eq!(
+        repair.validate(&[(2, d), (3, s)]),
+        Err(PathTraceError::NotFromRoot { first: 2 })
+    );
+    assert_eq!(
+        error(&[client, (2, d), (2, d), (3, s)]),
+        PathTraceError::SelfLoop(2)
+    );
+    assert_eq!(ids(&[client, (2, d), (2, d), (3, s)]), vec![1, 2, 3]);
+    assert_eq!(error(&[client, client]), PathTraceError::SelfLoop(1));
+    assert_eq!(
+        error(&[client, (2, d), (3, d), (2, d), (4, s)]),
+        PathTraceError::Repeated(2)
+    );
+    assert_eq!(
+        ids(&[client, (2, d), (3, d), (2, d), (4, s)]),
+        vec![1, 2, 3]
+    );
+    assert_eq!(
+        error(&[client, (2, d), (3, s), (4, d)]),
+        PathTraceError::LeafForwarded {
+            id: 3,
+            node_type: s
+        }
+    );
+    assert_eq!(ids(&[client, (2, d), (3, s), (4, d)]), vec![1, 2, 3]);
+    assert!(strict.validate(&[client, (2, d), (3, s)]).is_ok());
+
+    // the root is never linked to itself
+    let mut network = Network::new(1, NodeType::Client);
+    network
+        .update_from_path_trace(&[client, (2, d), (3, s)], None, &repair)
+        .unwrap();
+    assert!(!network.get(1).unwrap().neighbours.contains(1));
+    assert_eq!(network.get_link_number(), 2);
+}
+
+#[test]
+fn test_path_trace_fuzz() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(22);
+    for repair in [true, false] {
+        let validator = PathTraceValidator::new(1).with_repair(repair);
+        let mut network = Network::new(1, NodeType::Client);
+        for _ in 0..2000 {
+            let len = rng.random_range(0..8);
+            let trace: Vec<(NodeId, NodeType)> = (0..len)
+                .map(|_| {
+                    let node_type = match rng.random_range(0..6) {
+                        0 => NodeType::Client,
+                        1 => NodeType::Server,
+                        _ => NodeType::Drone,
+                    };
+                    (rng.random_range(0..12), node_type)
+                })
+                .collect();
+            let validated = validator.validate(&trace);
+            let learned =
+                network.update_from_path_trace(&trace, Some(rng.random_range(0..4)), &validator);
+            assert_eq!(validated.is_ok(), learned.is_ok());
+            if let Ok(valid) = validated {
+                assert_eq!(valid[0].0, 1);
+                let crossed: NodeSet = valid.iter().map(|(id, _)| *id).collect();
+                assert_eq!(crossed.len(), valid.len());
+                // only drones between the ends
+                assert!(valid
+                    .iter()
+                    .skip(1)
+                    .rev()
+                    .skip(1)
+                    .all(|(_, node_type)| *node_type == NodeType::Drone));
+            }
+            if rng.random_bool(0.05) {
+                let _ = network.remove_node(rng.random_range(0..12));
+            }
+        }
+        for (id, node) in network.iter_nodes() {
+            assert!(!node.neighbours.contains(id));
+            for neighbour in node.neighbours {
+                assert!(network.get(neighbour).unwrap().neighbours.contains(id));
+            }
+        }
+        assert!(network.routing_table().is_ok());
+    }
+}
+
+#[test]
+fn test_node_type_reconciliation() {
+    let (d, s) = (NodeType::Drone, NodeType::Server);
+    let mut network = Network::new(1, NodeType::Client);
+    // 2 is guessed to be a drone
+    network.add_neighbour(2).unwrap();
+    network.add_neighbour(3).unwrap();
+    let _ = network.add_empty_node(5, s);
+    let _ = network.add_link(2, 5);
+    let _ = network.add_link(3, 5);
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 2, 5]);
+    assert!(network.add_neighbour(1).is_err());
+
+    // the path trace is authoritative
+    let conflicts = network
+        .update_from_path_trace(&[(1, NodeType::Client), (2, s)], Some(0), &validator())
+        .unwrap();
+    assert_eq!(
+        conflicts,
+        vec![TypeConflict {
+            id: 2,
+            previous: d,
+            observed: s,
+            guessed: true,
+            flood_id: Some(0),
+        }]
+    );
+    assert_eq!(network.node_type(2), Some(s));
+    assert_eq!(network.get_server_list(), HashSet::from([2, 5]));
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 3, 5]);
+
+    // the same type again is not a conflict, a different one is
+    assert!(network
+        .update_from_path_trace(&[(1, NodeType::Client), (2, s)], Some(1), &validator())
+        .unwrap()
+        .is_empty());
+    let conflicts = network
+        .update_from_path_trace(
+            &[(1, NodeType::Client), (2, d), (5, s)],
+            Some(2),
+            &validator(),
+        )
+        .unwrap();
+    assert_eq!(
+        conflicts
+            .iter()
+            .map(|conflict| (conflict.id, conflict.guessed))
+            .collect::<Vec<_>>(),
+        vec![(2, false)]
+    );
+    assert_eq!(network.get_server_list(), HashSet::from([5]));
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 2, 5]);
+
+    // but not the type seen by an older flood
+    assert!(network
+        .update_from_path_trace(&[(1, NodeType::Client), (2, s)], Some(1), &validator())
+        .unwrap()
+        .is_empty());
+    assert_eq!(network.node_type(2), Some(d));
+
+    // an already known node keeps its type when it becomes a neighbour
+    let _ = network.add_empty_node(4, s);
+    network.add_neighbour(4).unwrap();
+    assert_eq!(network.node_type(4), Some(s));
+    assert!(network.get(1).unwrap().neighbours.contains(4));
+}
+
+#[test]
+fn test_time_decay_refresh() {
+    use std::time::Duration;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut network = Network::new(1, NodeType::Client);
+    network.set_clock(clock.clone());
+    network.set_weight_decay(WeightDecay::Time {
+        half_life: Duration::from_secs(60),
+    });
+    for id in 2..=5 {
+        let _ = network.add_empty_node(id, NodeType::Drone);
+    }
+    // 1 => 2 => 5
+    // 1 => 3 => 4 => 5
+    let _ = network.add_link(1, 2);
+    let _ = network.add_link(2, 5);
+    let _ = network.add_link(1, 3);
+    let _ = network.add_link(3, 4);
+    let _ = network.add_link(4, 5);
+    for _ in 0..4 {
+        let _ = network.increment_weight(2);
+    }
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 3, 4, 5]);
+
+    // the cached routes and the epoch follow the decayed weights
+    let epoch = network.epoch();
+    clock.advance(Duration::from_secs(14));
+    assert_eq!(network.epoch(), epoch);
+    clock.advance(Duration::from_secs(60 * 4));
+    assert!(network.epoch() > epoch);
+    assert_eq!(network.get_routes(5).unwrap(), vec![1, 2, 5]);
+}
@@ -0,0 +1,97 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::Network;
+use log::info;
+use std::time::{Duration, Instant};
+use wg_2024::{network::NodeId, packet::NodeType};
+
+/// How long a crashed drone or a removed link stays buried,
+/// see `Router::set_tombstone_lifetime`
+pub const TOMBSTONE_LIFETIME: Duration = Duration::from_secs(60);
+
+/// A crashed node or a removed link, not to be learned again from old path traces
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Tombstone {
+    buried: Instant,
+    /// Only the floods from this id on were requested after the burial
+    next_flood: u64,
+}
+
+impl Network {
+    //methods
+    /// Keeps `id` from being learned again from the path traces of the floods
+    /// before `next_flood`, for the tombstone lifetime. The node itself is removed
+    /// with `remove_node`
+    pub fn bury_node(&mut self, id: NodeId, next_flood: u64) {
+        if id != self.root {
+            self.node_tombstones[usize::from(id)] = Some(self.tombstone(next_flood));
+        }
+    }
+    /// Like `bury_node`, for the link between `id1` and `id2`
+    pub fn bury_link(&mut self, id1: NodeId, id2: NodeId, next_flood: u64) {
+        let tombstone = self.tombstone(next_flood);
+        self.link_tombstones.insert(id1, id2, tombstone);
+    }
+    /// Lets `id` and its link with the root be learned again, as when it is added
+    /// back as a neighbour
+    pub(super) fn unbury_neighbour(&mut self, id: NodeId) {
+        self.node_tombstones[usize::from(id)] = None;
+        self.link_tombstones.remove(self.root, id);
+    }
+    fn tombstone(&self, next_flood: u64) -> Tombstone {
+        Tombstone {
+            buried: self.clock.now(),
+            next_flood,
+        }
+    }
+    /// Cuts `path_trace` before the first buried node or link. A trace of a flood
+    /// requested after the burial is newer evidence and removes the tombstones it crosses
+    pub(super) fn cut_at_tombstones(
+        &mut self,
+        mut path_trace: Vec<(NodeId, NodeType)>,
+        flood_id: Option<u64>,
+    ) -> Vec<(NodeId, NodeType)> {
+        let now = self.clock.now();
+        let lifetime = self.tombstone_lifetime;
+        // the tombstone is removed unless it still hides the node or link
+        let outlived = |tombstone: &Tombstone| {
+            now.saturating_duration_since(tombstone.buried) >= lifetime
+                || flood_id.is_some_and(|flood_id| flood_id >= tombstone.next_flood)
+        };
+        let mut previous: Option<NodeId> = None;
+        let buried = path_trace.iter().position(|(id, _)| {
+            let node = &mut self.node_tombstones[usize::from(*id)];
+            if node.as_ref().is_some_and(outlived) {
+                *node = None;
+            }
+            let node_buried = node.is_some();
+            let link_buried = previous.is_some_and(|previous| {
+                if self
+                    .link_tombstones
+                    .get(previous, *id)
+                    .is_some_and(outlived)
+                {
+                    self.link_tombstones.remove(previous, *id);
+                }
+                self.link_tombstones.get(previous, *id).is_some()
+            });
+            previous = Some(*id);
+            node_buried || link_buried
+        });
+        if let Some(index) = buried {
+            info!(
+                "[Network of {}] path trace cut at the buried {}",
+                self.root, path_trace[index].0
+            );
+            path_trace.truncate(index);
+        }
+        path_trace
+    }
+}
+
+impl Network {
+    //getter/setter
+    pub fn set_tombstone_lifetime(&mut self, lifetime: Duration) {
+        self.tombstone_lifetime = lifetime;
+    }
+}
@@ -0,0 +1,85 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::time::{Duration, Instant};
+
+/// With `WeightDecay::Time` the routes are recomputed this many times per half life,
+/// so that they follow the decayed weights
+pub const DECAY_REFRESHES_PER_HALF_LIFE: u32 = 4;
+
+/// How the drops counted on a link lose importance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightDecay {
+    /// Drops are never forgotten
+    None,
+    /// The weight of a link halves every `half_life`
+    Time { half_life: Duration },
+    /// The weight of a link halves every `half_life` drops recorded in the network
+    Observations { half_life: u64 },
+}
+
+impl Default for WeightDecay {
+    fn default() -> Self {
+        Self::Time {
+            half_life: Duration::from_secs(60),
+        }
+    }
+}
+
+impl WeightDecay {
+    /// How often the decayed weights are taken into account by the routes,
+    /// `None` if they only change when a drop is recorded
+    pub fn refresh_interval(self) -> Option<Duration> {
+        match self {
+            Self::Time { half_life } if !half_life.is_zero() => {
+                Some(half_life / DECAY_REFRESHES_PER_HALF_LIFE)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Drops on a link, stored with the moment of the last update
+/// so that the decayed value can be computed without mutating it
+#[derive(Debug, Clone, Copy)]
+pub struct DecayingWeight {
+    value: f64,
+    updated: Instant,
+    observation: u64,
+}
+
+impl DecayingWeight {
+    //constructor
+    pub fn new(value: f64, now: Instant, observation: u64) -> Self {
+        Self {
+            value,
+            updated: now,
+            observation,
+        }
+    }
+}
+
+impl DecayingWeight {
+    //methods
+    /// Value of the weight at `now`, when `observation` drops have been recorded
+    #[allow(clippy::cast_precision_loss)]
+    pub fn value(&self, decay: WeightDecay, now: Instant, observation: u64) -> f64 {
+        let half_lives = match decay {
+            WeightDecay::None => return self.value,
+            WeightDecay::Time { half_life } if half_life.is_zero() => return 0.0,
+            WeightDecay::Time { half_life } => {
+                now.saturating_duration_since(self.updated).as_secs_f64() / half_life.as_secs_f64()
+            }
+            WeightDecay::Observations { half_life: 0 } => return 0.0,
+            WeightDecay::Observations { half_life } => {
+                observation.saturating_sub(self.observation) as f64 / half_life as f64
+            }
+        };
+        self.value * 0.5f64.powf(half_lives)
+    }
+    /// Decays the weight up to now and adds `amount`
+    pub fn add(&mut self, amount: f64, decay: WeightDecay, now: Instant, observation: u64) {
+        self.value = self.value(decay, now, observation) + amount;
+        self.updated = now;
+        self.observation = observation;
+    }
+}
@@ -0,0 +1,117 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::collections::HashMap;
+use wg_2024::network::NodeId;
+
+/// Weight of the last observation in the exponential moving average
+pub const PDR_EWMA_ALPHA: f64 = 0.1;
+/// z-score of the confidence bounds (95%)
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// Estimated packet drop rate of a drone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PdrEstimate {
+    /// Mean of the Beta(1 + dropped, 1 + delivered) posterior
+    pub mean: f64,
+    /// Lower bound of the 95% confidence interval
+    pub lower: f64,
+    /// Upper bound of the 95% confidence interval
+    pub upper: f64,
+    /// Exponential moving average of the outcomes, follows changes faster than `mean`
+    pub ewma: f64,
+    /// Packets routed through the drone
+    pub sent: u64,
+    /// Packets forwarded by the drone and acked by the destination
+    pub delivered: u64,
+    /// Packets dropped by the drone
+    pub dropped: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DroneCounters {
+    sent: u64,
+    delivered: u64,
+    dropped: u64,
+    ewma: Option<f64>,
+}
+
+impl DroneCounters {
+    fn observe(&mut self, dropped: bool) {
+        let outcome = if dropped { 1.0 } else { 0.0 };
+        self.ewma = Some(
+            self.ewma
+                .map_or(outcome, |e| e + PDR_EWMA_ALPHA * (outcome - e)),
+        );
+    }
+    #[allow(clippy::cast_precision_loss)]
+    fn estimate(&self) -> PdrEstimate {
+        let alpha = 1.0 + self.dropped as f64;
+        let beta = 1.0 + self.delivered as f64;
+        let mean = alpha / (alpha + beta);
+        let variance = alpha * beta / ((alpha + beta).powi(2) * (alpha + beta + 1.0));
+        let margin = CONFIDENCE_Z * variance.sqrt();
+        PdrEstimate {
+            mean,
+            lower: (mean - margin).max(0.0),
+            upper: (mean + margin).min(1.0),
+            ewma: self.ewma.unwrap_or(mean),
+            sent: self.sent,
+            delivered: self.delivered,
+            dropped: self.dropped,
+        }
+    }
+}
+
+/// Keeps the sent/acked/dropped counters of every drone
+#[derive(Debug, Clone, Default)]
+pub struct PdrEstimator {
+    counters: HashMap<NodeId, DroneCounters>,
+}
+
+impl PdrEstimator {
+    //constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PdrEstimator {
+    //methods
+    /// `hops` is the full path, source and destination are not counted
+    pub fn packet_sent(&mut self, hops: &[NodeId]) {
+        for id in intermediates(hops) {
+            self.counters.entry(*id).or_default().sent += 1;
+        }
+    }
+    /// Every drone in `hops` forwarded the packet
+    /// # Returns
+    /// The drones whose estimate changed
+    pub fn packet_acked(&mut self, hops: &[NodeId]) -> Vec<NodeId> {
+        for id in intermediates(hops) {
+            let counters = self.counters.entry(*id).or_default();
+            counters.delivered += 1;
+            counters.observe(false);
+        }
+        intermediates(hops).to_vec()
+    }
+    pub fn packet_dropped_at(&mut self, id: NodeId) {
+        let counters = self.counters.entry(id).or_default();
+        counters.dropped += 1;
+        counters.observe(true);
+    }
+    /// Returns `None` if the drone was never observed
+    pub fn estimate(&self, id: NodeId) -> Option<PdrEstimate> {
+        self.counters.get(&id).map(DroneCounters::estimate)
+    }
+    pub fn estimates(&self) -> impl Iterator<Item = (NodeId, PdrEstimate)> + '_ {
+        self.counters.iter().map(|(id, c)| (*id, c.estimate()))
+    }
+}
+
+fn intermediates(hops: &[NodeId]) -> &[NodeId] {
+    if hops.len() > 2 {
+        &hops[1..hops.len() - 1]
+    } else {
+        &[]
+    }
+}
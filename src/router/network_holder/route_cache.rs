@@ -0,0 +1,67 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+use wg_2024::network::NodeId;
+
+/// Hits and misses of the route cache, see `Router::route_cache_stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RouteCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Topology epoch of the cached routes
+    pub epoch: u64,
+}
+
+/// Paths already computed for each destination, valid only for the topology epoch
+/// they were computed in
+#[derive(Debug, Default)]
+pub struct RouteCache {
+    epoch: Cell<u64>,
+    paths: RefCell<HashMap<NodeId, Vec<NodeId>>>,
+    hits: Cell<u64>,
+    misses: Cell<u64>,
+}
+
+impl RouteCache {
+    //constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RouteCache {
+    //methods
+    /// Returns the cached path to `destination` if it was computed in `epoch`,
+    /// otherwise computes it with `compute` and caches it when found
+    /// # Errors
+    /// - the error of `compute`
+    pub fn get_or_compute<E>(
+        &self,
+        epoch: u64,
+        destination: NodeId,
+        compute: impl FnOnce() -> Result<Vec<NodeId>, E>,
+    ) -> Result<Vec<NodeId>, E> {
+        if self.epoch.get() != epoch {
+            self.paths.borrow_mut().clear();
+            self.epoch.set(epoch);
+        }
+        if let Some(path) = self.paths.borrow().get(&destination) {
+            self.hits.set(self.hits.get() + 1);
+            return Ok(path.clone());
+        }
+        self.misses.set(self.misses.get() + 1);
+        let path = compute()?;
+        self.paths.borrow_mut().insert(destination, path.clone());
+        Ok(path)
+    }
+    pub fn stats(&self) -> RouteCacheStats {
+        RouteCacheStats {
+            hits: self.hits.get(),
+            misses: self.misses.get(),
+            epoch: self.epoch.get(),
+        }
+    }
+}
@@ -0,0 +1,499 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::*;
+use wg_2024::packet::NodeType;
+
+#[test]
+fn test_pdr_estimate() {
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    assert!(holder.estimated_pdr(2).is_none());
+
+    for _ in 0..9 {
+        holder.packet_sent(&[1, 2, 3, 4]);
+        holder.packet_acked(&[1, 2, 3, 4]);
+    }
+    holder.packet_sent(&[1, 2, 3, 4]);
+    holder.packet_dropped_at(3);
+
+    let two = holder.estimated_pdr(2).unwrap();
+    let three = holder.estimated_pdr(3).unwrap();
+    assert_eq!((two.sent, two.delivered, two.dropped), (10, 9, 0));
+    assert_eq!((three.sent, three.delivered, three.dropped), (10, 9, 1));
+    assert!(two.mean < three.mean);
+    assert!(three.lower <= three.mean && three.mean <= three.upper);
+    assert!(three.ewma > two.ewma);
+    // source and destination are not estimated
+    assert!(holder.estimated_pdr(1).is_none());
+    assert!(holder.estimated_pdr(4).is_none());
+}
+
+#[test]
+fn test_pdr_routing() {
+    let mut holder = NetworkHolder::with_metric(
+        1,
+        NodeType::Client,
+        Arc::new(DeliveryProbability::default()),
+    );
+    holder.asked_flood_request(0..1);
+    // 1 => 2 => 4
+    // 1 => 3 => 4
+    holder.received_flood_response(&FloodResponse {
+        flood_id: 0,
+        path_trace: vec![
+            (1, NodeType::Client),
+            (2, NodeType::Drone),
+            (4, NodeType::Server),
+        ],
+    });
+    holder.received_flood_response(&FloodResponse {
+        flood_id: 0,
+        path_trace: vec![
+            (1, NodeType::Client),
+            (3, NodeType::Drone),
+            (4, NodeType::Server),
+        ],
+    });
+
+    for _ in 0..5 {
+        holder.packet_acked(&[1, 3, 4]);
+        holder.packet_dropped_at(2);
+    }
+    assert_eq!(holder.get_path(4).unwrap(), vec![1, 3, 4]);
+    for _ in 0..20 {
+        holder.packet_acked(&[1, 2, 4]);
+        holder.packet_dropped_at(3);
+    }
+    assert_eq!(holder.get_path(4).unwrap(), vec![1, 2, 4]);
+}
+
+#[test]
+fn test_route_cache() {
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    holder.asked_flood_request(0..1);
+    let response = FloodResponse {
+        flood_id: 0,
+        path_trace: vec![
+            (1, NodeType::Client),
+            (2, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    };
+    holder.received_flood_response(&response);
+    let epoch = holder.topology_epoch();
+
+    assert_eq!(holder.get_path(3).unwrap(), vec![1, 2, 3]);
+    assert_eq!(holder.get_path(3).unwrap(), vec![1, 2, 3]);
+    let stats = holder.route_cache_stats();
+    assert_eq!((stats.hits, stats.misses, stats.epoch), (1, 1, epoch));
+
+    // an already known trace does not change the topology
+    holder.received_flood_response(&response);
+    assert_eq!(holder.topology_epoch(), epoch);
+    let _ = holder.get_path(3);
+    assert_eq!(holder.route_cache_stats().hits, 2);
+
+    holder.add_neighbour(4);
+    holder.received_flood_response(&FloodResponse {
+        flood_id: 0,
+        path_trace: vec![
+            (1, NodeType::Client),
+            (4, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    });
+    holder.dropped_fragment(2);
+    assert!(holder.topology_epoch() > epoch);
+    assert_eq!(holder.get_path(3).unwrap(), vec![1, 4, 3]);
+    assert_eq!(holder.route_cache_stats().misses, 2);
+
+    let epoch = holder.topology_epoch();
+    holder.asked_flood_request(1..2);
+    holder.received_flood_response(&response);
+    assert!(holder.topology_epoch() > epoch);
+}
+
+#[test]
+fn test_partition() {
+    use std::sync::Mutex;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    holder.set_clock(clock.clone());
+    holder.asked_flood_request(0..1);
+    // 1 => 2 => 3 => 5
+    // 1 => 4 => 6
+    for path_trace in [
+        vec![
+            (1, NodeType::Client),
+            (2, NodeType::Drone),
+            (3, NodeType::Drone),
+            (5, NodeType::Server),
+        ],
+        vec![
+            (1, NodeType::Client),
+            (4, NodeType::Drone),
+            (6, NodeType::Server),
+        ],
+    ] {
+        holder.received_flood_response(&FloodResponse {
+            flood_id: 0,
+            path_trace,
+        });
+    }
+    // the round is complete, its generation is used for routing
+    clock.advance(FLOOD_ROUND_TIMEOUT);
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let sink = reported.clone();
+    holder.on_partition(move |partition| sink.lock().unwrap().push(partition.clone()));
+
+    assert!(!holder.partition().is_partitioned());
+    assert_eq!(holder.get_server_list(), HashSet::from([5, 6]));
+
+    holder.drone_crashed(2);
+    let partition = holder.partition();
+    assert_eq!(partition.orphaned, vec![HashSet::from([3, 5])]);
+    assert_eq!(partition.reachable, HashSet::from([1, 4, 6]));
+    assert_eq!(holder.get_server_list(), HashSet::from([6]));
+    assert_eq!(holder.get_unreachable_server_list(), HashSet::from([5]));
+    assert_eq!(*reported.lock().unwrap(), vec![partition.clone()]);
+    // the same partition is reported once
+    holder.drone_crashed(2);
+    holder.remove_neighbour(7);
+    assert_eq!(*reported.lock().unwrap(), vec![partition]);
+
+    holder.set_prune_orphans(true);
+    assert!(!holder.partition().is_partitioned());
+    assert!(holder.get_unreachable_server_list().is_empty());
+    assert!(!holder.current().contains_id(3));
+}
+
+#[test]
+fn test_generations() {
+    let clock = Arc::new(ManualClock::new());
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    holder.set_clock(clock.clone());
+    let response = |flood_id, path_trace: &[NodeId]| FloodResponse {
+        flood_id,
+        path_trace: path_trace
+            .iter()
+            .map(|id| {
+                let node_type = match id {
+                    1 => NodeType::Client,
+                    5.. => NodeType::Server,
+                    _ => NodeType::Drone,
+                };
+                (*id, node_type)
+            })
+            .collect(),
+    };
+    let summary = |holder: &NetworkHolder| {
+        holder
+            .generations()
+            .iter()
+            .map(|generation| (generation.flood_id, generation.complete, generation.nodes))
+            .collect::<Vec<_>>()
+    };
+
+    holder.asked_flood_request(0..1);
+    holder.received_flood_response(&response(0, &[1, 2, 5]));
+    holder.received_flood_response(&response(0, &[1, 3, 5]));
+    // a generation is complete once its round is
+    assert_eq!(summary(&holder), vec![(None, true, 1), (Some(0), false, 4)]);
+    clock.advance(FLOOD_ROUND_TIMEOUT);
+    assert_eq!(summary(&holder), vec![(None, true, 1), (Some(0), true, 4)]);
+
+    // the new round is used once it is complete
+    holder.asked_flood_request(1..2);
+    holder.received_flood_response(&response(1, &[1, 2, 4, 5]));
+    assert_eq!(holder.get_path(5).unwrap(), vec![1, 2, 5]);
+    clock.advance(FLOOD_ROUND_TIMEOUT);
+    assert_eq!(holder.get_path(5).unwrap(), vec![1, 2, 4, 5]);
+    // the confidence in the links is carried over from the previous round
+    assert_eq!(holder.link_confidence(1, 2).unwrap().floods, 2);
+    assert_eq!(holder.link_confidence(2, 4).unwrap().floods, 1);
+
+    // the responses of a rolled back round are not learned anywhere
+    holder.asked_flood_request(2..3);
+    holder.received_flood_response(&response(2, &[1, 2, 6]));
+    assert_eq!(
+        holder.rollback_generation().map(|info| info.flood_id),
+        Some(Some(2))
+    );
+    holder.received_flood_response(&response(2, &[1, 3, 6]));
+    assert_eq!(holder.node_type(6), None);
+    assert_eq!(holder.get_path(5).unwrap(), vec![1, 2, 4, 5]);
+
+    // nor are the ones of a forgotten round,
+    // and the generation used for routing is never forgotten
+    holder.set_generation_limit(2);
+    holder.asked_flood_request(3..4);
+    assert_eq!(
+        summary(&holder),
+        vec![(Some(1), true, 4), (Some(3), false, 1)]
+    );
+    holder.received_flood_response(&response(0, &[1, 2, 7]));
+    assert_eq!(holder.node_type(7), None);
+    assert_eq!(holder.get_path(5).unwrap(), vec![1, 2, 4, 5]);
+}
+
+#[test]
+fn test_generation_weights() {
+    let clock = Arc::new(ManualClock::new());
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    holder.set_clock(clock.clone());
+    let response = |flood_id, path_trace: &[NodeId]| FloodResponse {
+        flood_id,
+        path_trace: path_trace
+            .iter()
+            .map(|id| {
+                let node_type = match id {
+                    1 => NodeType::Client,
+                    9 => NodeType::Server,
+                    _ => NodeType::Drone,
+                };
+                (*id, node_type)
+            })
+            .collect(),
+    };
+    let flood = |holder: &mut NetworkHolder, flood_id| {
+        holder.asked_flood_request(flood_id..flood_id + 1);
+        holder.received_flood_response(&response(flood_id, &[1, 2, 9]));
+        holder.received_flood_response(&response(flood_id, &[1, 3, 9]));
+        clock.advance(FLOOD_ROUND_TIMEOUT);
+    };
+
+    flood(&mut holder, 0);
+    for _ in 0..3 {
+        holder.dropped_fragment(2);
+    }
+    assert_eq!(holder.get_path(9).unwrap(), vec![1, 3, 9]);
+
+    // the new generation used for routing still knows the drops recorded before
+    flood(&mut holder, 1);
+    assert_eq!(
+        holder
+            .generations()
+            .last()
+            .map(|info| (info.flood_id, info.complete)),
+        Some((Some(1), true))
+    );
+    assert_eq!(holder.get_path(9).unwrap(), vec![1, 3, 9]);
+}
+
+#[test]
+fn test_flood_rounds() {
+    use std::time::Duration;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    holder.set_clock(clock.clone());
+    let response = |flood_id, path_trace: Vec<(NodeId, NodeType)>| FloodResponse {
+        flood_id,
+        path_trace,
+    };
+
+    holder.asked_flood_request(0..2);
+    assert!(holder.flood_round(2).is_none());
+    clock.advance(Duration::from_secs(1));
+    holder.received_flood_response(&response(
+        0,
+        vec![
+            (1, NodeType::Client),
+            (2, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    ));
+    holder.received_flood_response(&response(
+        0,
+        vec![
+            (1, NodeType::Client),
+            (2, NodeType::Drone),
+            (4, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    ));
+    holder.received_flood_response(&response(
+        0,
+        vec![
+            (1, NodeType::Client),
+            (2, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    ));
+
+    let round = holder.flood_round(0).unwrap();
+    assert_eq!(
+        (round.responses, round.new_nodes, round.new_links),
+        (3, 3, 4)
+    );
+    assert_eq!(round.state, FloodRoundState::Pending);
+    assert_eq!(
+        round.last_response,
+        Some(round.started + Duration::from_secs(1))
+    );
+
+    clock.advance(FLOOD_ROUND_TIMEOUT);
+    let rounds = holder.flood_rounds();
+    assert_eq!(
+        rounds
+            .iter()
+            .map(|round| (round.flood_id, round.state))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, FloodRoundState::Completed),
+            (1, FloodRoundState::TimedOut)
+        ]
+    );
+
+    // a late response is still learned in the generation of its round,
+    // which it completes
+    holder.received_flood_response(&response(
+        1,
+        vec![
+            (1, NodeType::Client),
+            (5, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    ));
+    let round = holder.flood_round(1).unwrap();
+    assert_eq!((round.responses, round.new_nodes), (1, 1));
+    assert_eq!(round.state, FloodRoundState::Completed);
+    assert_eq!(holder.node_type(5), Some(NodeType::Drone));
+
+    // a round whose responses all arrive after the timeout is not lost
+    holder.asked_flood_request(2..3);
+    clock.advance(FLOOD_ROUND_TIMEOUT);
+    holder.received_flood_response(&response(
+        2,
+        vec![
+            (1, NodeType::Client),
+            (6, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    ));
+    assert_eq!(
+        holder.flood_round(2).unwrap().state,
+        FloodRoundState::Completed
+    );
+    assert_eq!(holder.get_path(3).unwrap(), vec![1, 6, 3]);
+}
+
+#[test]
+fn test_discovery_state() {
+    use std::time::Duration;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    holder.set_clock(clock.clone());
+    let rule = ConvergenceRule::default();
+    let response = |flood_id| FloodResponse {
+        flood_id,
+        path_trace: vec![
+            (1, NodeType::Client),
+            (2, NodeType::Drone),
+            (3, NodeType::Server),
+        ],
+    };
+    assert_eq!(holder.discovery_state().status, DiscoveryStatus::Stale);
+
+    holder.asked_flood_request(0..1);
+    assert_eq!(
+        holder.discovery_state().status,
+        DiscoveryStatus::Discovering
+    );
+    holder.received_flood_response(&response(0));
+    let state = holder.discovery_state();
+    assert_eq!(state.status, DiscoveryStatus::Discovering);
+    assert_eq!((state.nodes, state.links, state.servers), (3, 2, 1));
+
+    // converged after enough responses teaching nothing
+    for _ in 0..rule.quiet_responses {
+        holder.received_flood_response(&response(0));
+    }
+    let state = holder.discovery_state();
+    assert_eq!(state.status, DiscoveryStatus::Converged);
+    assert_eq!(
+        (state.responses, state.quiet_responses),
+        (rule.quiet_responses + 1, rule.quiet_responses)
+    );
+
+    // or when nothing new arrives for a while
+    holder.asked_flood_request(1..2);
+    // the coverage is the one of the new round, not of the network used for routing
+    let state = holder.discovery_state();
+    assert_eq!((state.nodes, state.links, state.servers), (1, 0, 0));
+    holder.received_flood_response(&response(1));
+    // responses of older floods are not counted
+    holder.received_flood_response(&response(0));
+    assert_eq!(holder.discovery_state().responses, 1);
+    assert_eq!(
+        holder.discovery_state().status,
+        DiscoveryStatus::Discovering
+    );
+    clock.advance(rule.quiet_time);
+    assert_eq!(holder.discovery_state().status, DiscoveryStatus::Converged);
+
+    clock.advance(rule.stale_after);
+    assert_eq!(holder.discovery_state().status, DiscoveryStatus::Stale);
+    holder.set_convergence_rule(ConvergenceRule {
+        stale_after: Duration::MAX,
+        ..rule
+    });
+    assert_eq!(holder.discovery_state().status, DiscoveryStatus::Converged);
+}
+
+#[test]
+fn test_tombstones() {
+    use std::time::Duration;
+
+    let clock = Arc::new(ManualClock::new());
+    let mut holder = NetworkHolder::new(1, NodeType::Client);
+    holder.set_clock(clock.clone());
+    let response = |flood_id, path_trace: Vec<(NodeId, NodeType)>| FloodResponse {
+        flood_id,
+        path_trace,
+    };
+    let trace = vec![
+        (1, NodeType::Client),
+        (2, NodeType::Drone),
+        (3, NodeType::Drone),
+        (4, NodeType::Server),
+    ];
+
+    holder.asked_flood_request(0..1);
+    holder.drone_crashed(3);
+    // a response of a flood requested before the crash is cut at the buried drone
+    holder.received_flood_response(&response(0, trace.clone()));
+    assert_eq!(holder.node_type(2), Some(NodeType::Drone));
+    assert_eq!(holder.node_type(3), None);
+    assert_eq!(holder.node_type(4), None);
+
+    // a flood requested after the crash brings it back
+    holder.asked_flood_request(1..2);
+    holder.received_flood_response(&response(1, trace.clone()));
+    assert_eq!(holder.node_type(3), Some(NodeType::Drone));
+    assert_eq!(holder.node_type(4), Some(NodeType::Server));
+
+    // as does the end of the tombstone lifetime
+    holder.set_tombstone_lifetime(Duration::from_secs(1));
+    holder.drone_crashed(3);
+    holder.received_flood_response(&response(1, trace.clone()));
+    assert_eq!(holder.node_type(3), None);
+    clock.advance(Duration::from_secs(1));
+    holder.received_flood_response(&response(1, trace));
+    assert_eq!(holder.node_type(3), Some(NodeType::Drone));
+
+    // a removed neighbour link stays buried until the neighbour is added back
+    let trace = vec![
+        (1, NodeType::Client),
+        (2, NodeType::Drone),
+        (5, NodeType::Drone),
+    ];
+    holder.add_neighbour(2);
+    holder.remove_neighbour(2);
+    holder.received_flood_response(&response(1, trace.clone()));
+    assert_eq!(holder.node_type(5), None);
+    holder.add_neighbour(2);
+    holder.received_flood_response(&response(1, trace));
+    assert_eq!(holder.node_type(5), Some(NodeType::Drone));
+}
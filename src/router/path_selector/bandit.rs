@@ -0,0 +1,61 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use std::collections::HashMap;
+use wg_2024::network::NodeId;
+
+/// Result of a packet sent on a route, see `Router::report_outcome`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The destination acked the packet
+    Acked,
+    /// A drone of the route nacked the packet
+    Nacked,
+}
+
+/// Statistics of a route used as an arm of the UCB1 bandit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Arm {
+    selected: u64,
+    acked: u64,
+    nacked: u64,
+}
+
+impl Arm {
+    //methods
+    pub fn selected(&mut self) {
+        self.selected += 1;
+    }
+    pub fn report(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Acked => self.acked += 1,
+            Outcome::Nacked => self.nacked += 1,
+        }
+    }
+    fn reported(&self) -> u64 {
+        self.acked + self.nacked
+    }
+    /// Fraction of acked packets plus the UCB1 exploration bonus
+    #[allow(clippy::cast_precision_loss)]
+    fn upper_bound(&self, total_reported: u64) -> f64 {
+        let reported = self.reported() as f64;
+        self.acked as f64 / reported + (2.0 * (total_reported as f64).ln() / reported).sqrt()
+    }
+}
+
+/// Index of the route in `paths` with the highest upper confidence bound.
+/// Routes without any outcome yet are tried first, the least selected one before the others
+pub fn pick(arms: &HashMap<Vec<NodeId>, Arm>, paths: &[&Vec<NodeId>]) -> Option<usize> {
+    let arm = |index: usize| arms.get(paths[index]).copied().unwrap_or_default();
+    let untried = (0..paths.len())
+        .filter(|index| arm(*index).reported() == 0)
+        .min_by_key(|index| arm(*index).selected);
+    if untried.is_some() {
+        return untried;
+    }
+    let total_reported = (0..paths.len()).map(|index| arm(index).reported()).sum();
+    (0..paths.len()).max_by(|a, b| {
+        arm(*a)
+            .upper_bound(total_reported)
+            .total_cmp(&arm(*b).upper_bound(total_reported))
+    })
+}
@@ -0,0 +1,224 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use crate::error::{Result, RouterError::RouteNotFound};
+use bandit::Arm;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+use wg_2024::network::NodeId;
+
+mod bandit;
+#[cfg(test)]
+mod test;
+
+pub use bandit::Outcome;
+
+/// Number of routes computed for each destination, see `PathSelector`
+pub const PATH_SELECTOR_CANDIDATES: usize = 4;
+/// A route is a candidate only if `1 + cost <= PATH_SELECTOR_COST_RATIO * (1 + best cost)`
+pub const PATH_SELECTOR_COST_RATIO: f64 = 2.0;
+
+/// How `PathSelector` chooses among the candidate routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionStrategy {
+    /// Smooth weighted round-robin: deterministic, the routes are interleaved
+    /// and get a share of the traffic proportional to `1 / (1 + cost)`
+    #[default]
+    WeightedRoundRobin,
+    /// Every route is picked at random with a probability proportional to `1 / (1 + cost)`
+    CostProportional,
+    /// UCB1 bandit over all the `PATH_SELECTOR_CANDIDATES` routes, whatever their cost:
+    /// learns the most reliable one from the outcomes given to `Router::report_outcome`
+    Ucb,
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    path: Vec<NodeId>,
+    weight: f64,
+    /// Credit of the smooth weighted round-robin
+    current: f64,
+}
+
+/// Candidate routes of every destination, refreshed when the topology epoch changes
+#[derive(Debug)]
+pub struct PathSelector {
+    strategy: SelectionStrategy,
+    /// See `set_structure_epoch`
+    structure_epoch: u64,
+    /// Candidates of every destination, with the epoch they were computed at
+    candidates: HashMap<NodeId, (u64, Vec<Candidate>)>,
+    /// Outcomes of the routes used by `SelectionStrategy::Ucb`, kept across epochs
+    /// as long as the route is computed again
+    arms: HashMap<NodeId, HashMap<Vec<NodeId>, Arm>>,
+    /// Route prefixes that led somewhere else than expected, with the node actually
+    /// reached, see `mark_stale`. Forgotten when `structure_epoch` changes
+    stale: Vec<(Vec<NodeId>, NodeId)>,
+    rng: StdRng,
+}
+
+impl PathSelector {
+    //constructor
+    pub fn new(strategy: SelectionStrategy) -> Self {
+        Self {
+            strategy,
+            structure_epoch: 0,
+            candidates: HashMap::new(),
+            arms: HashMap::new(),
+            stale: Vec::new(),
+            rng: StdRng::from_os_rng(),
+        }
+    }
+}
+
+impl PathSelector {
+    //methods
+    /// Picks the route of the next packet to `destination`.
+    /// When the candidates are missing or `epoch` changed they are rebuilt
+    /// from the routes returned by `compute` (with their costs, the cheapest first),
+    /// leaving out the stale ones unless no other route is left.
+    /// The routes computed again keep their round-robin credit, so that the traffic
+    /// is still spread when the epoch changes between two packets
+    /// # Errors
+    /// - the error of `compute`
+    pub fn select(
+        &mut self,
+        epoch: u64,
+        destination: NodeId,
+        compute: impl FnOnce() -> Result<Vec<(Vec<NodeId>, u64)>>,
+    ) -> Result<Vec<NodeId>> {
+        let stale = &self.stale;
+        let (computed, candidates) = self.candidates.entry(destination).or_default();
+        if candidates.is_empty() || *computed != epoch {
+            let mut paths = compute()?;
+            if paths.iter().any(|(path, _)| !is_stale(stale, path)) {
+                paths.retain(|(path, _)| !is_stale(stale, path));
+            }
+            if let Some(arms) = self.arms.get_mut(&destination) {
+                arms.retain(|arm, _| paths.iter().any(|(path, _)| path == arm));
+            }
+            let mut refreshed = match self.strategy {
+                SelectionStrategy::Ucb => to_arms(paths),
+                _ => to_candidates(paths),
+            };
+            if refreshed.is_empty() {
+                return Err(Box::new(RouteNotFound { destination }));
+            }
+            for candidate in &mut refreshed {
+                if let Some(old) = candidates.iter().find(|old| old.path == candidate.path) {
+                    candidate.current = old.current;
+                }
+            }
+            *computed = epoch;
+            *candidates = refreshed;
+        }
+        let total: f64 = candidates.iter().map(|c| c.weight).sum();
+        let index = match self.strategy {
+            SelectionStrategy::WeightedRoundRobin => {
+                for candidate in candidates.iter_mut() {
+                    candidate.current += candidate.weight;
+                }
+                let index = (0..candidates.len())
+                    .max_by(|a, b| candidates[*a].current.total_cmp(&candidates[*b].current))
+                    .unwrap_or_else(|| unreachable!());
+                candidates[index].current -= total;
+                index
+            }
+            SelectionStrategy::CostProportional => {
+                let mut point = self.rng.random_range(0.0..total);
+                candidates
+                    .iter()
+                    .position(|c| {
+                        point -= c.weight;
+                        point < 0.0
+                    })
+                    .unwrap_or(candidates.len() - 1)
+            }
+            SelectionStrategy::Ucb => {
+                let arms = self.arms.entry(destination).or_default();
+                let paths: Vec<&Vec<NodeId>> = candidates.iter().map(|c| &c.path).collect();
+                let index = bandit::pick(arms, &paths).unwrap_or_else(|| unreachable!());
+                arms.entry(candidates[index].path.clone())
+                    .or_default()
+                    .selected();
+                index
+            }
+        };
+        Ok(candidates[index].path.clone())
+    }
+    /// Rewards (`Acked`) or penalises (`Nacked`) the route `path`,
+    /// used by `SelectionStrategy::Ucb`. Only the routes it selected are tracked
+    pub fn report(&mut self, path: &[NodeId], outcome: Outcome) {
+        let arm = path
+            .last()
+            .and_then(|destination| self.arms.get_mut(destination))
+            .and_then(|arms| arms.get_mut(path));
+        if let Some(arm) = arm {
+            arm.report(outcome);
+        }
+    }
+    /// A packet sent along `prefix` reached `reached` instead of the next hop of its
+    /// route: the routes continuing `prefix` elsewhere are not chosen again until
+    /// nodes or links are added or removed, see `set_structure_epoch`
+    pub fn mark_stale(&mut self, prefix: Vec<NodeId>, reached: NodeId) {
+        let stale = (prefix, reached);
+        if self.stale.contains(&stale) {
+            return;
+        }
+        self.stale.push(stale);
+        let stale = &self.stale;
+        self.candidates.retain(|_, (_, candidates)| {
+            !candidates
+                .iter()
+                .any(|candidate| is_stale(stale, &candidate.path))
+        });
+    }
+    /// Forgets the stale route prefixes when `structure_epoch` changed: nodes or links
+    /// have been added or removed since they were marked, a change of the costs
+    /// does not count
+    pub fn set_structure_epoch(&mut self, structure_epoch: u64) {
+        if self.structure_epoch != structure_epoch {
+            self.stale.clear();
+            self.structure_epoch = structure_epoch;
+        }
+    }
+    pub fn set_strategy(&mut self, strategy: SelectionStrategy) {
+        self.strategy = strategy;
+        self.candidates.clear();
+    }
+}
+
+/// `path` continues a stale prefix with another node than the one reached
+fn is_stale(stale: &[(Vec<NodeId>, NodeId)], path: &[NodeId]) -> bool {
+    stale.iter().any(|(prefix, reached)| {
+        path.starts_with(prefix) && path.get(prefix.len()).is_some_and(|next| next != reached)
+    })
+}
+
+/// Keeps the routes not too expensive compared to the cheapest one
+/// # Note
+/// `paths` must be sorted by cost
+#[allow(clippy::cast_precision_loss)]
+fn to_candidates(paths: Vec<(Vec<NodeId>, u64)>) -> Vec<Candidate> {
+    let best = paths.first().map_or(0, |(_, cost)| *cost) as f64;
+    paths
+        .into_iter()
+        .filter(|(_, cost)| 1.0 + *cost as f64 <= PATH_SELECTOR_COST_RATIO * (1.0 + best))
+        .map(|(path, cost)| Candidate {
+            path,
+            weight: 1.0 / (1.0 + cost as f64),
+            current: 0.0,
+        })
+        .collect()
+}
+
+/// Every route is an arm of the bandit
+fn to_arms(paths: Vec<(Vec<NodeId>, u64)>) -> Vec<Candidate> {
+    paths
+        .into_iter()
+        .map(|(path, _)| Candidate {
+            path,
+            weight: 1.0,
+            current: 0.0,
+        })
+        .collect()
+}
@@ -0,0 +1,99 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::*;
+
+#[test]
+fn test_weighted_round_robin() {
+    let mut selector = PathSelector::new(SelectionStrategy::WeightedRoundRobin);
+    // 1 + 1 <= 2 * (1 + 0), but 1 + 5 is too expensive
+    let paths = vec![(vec![1, 2, 9], 0), (vec![1, 3, 9], 1), (vec![1, 4, 9], 5)];
+
+    let mut counts = HashMap::new();
+    for _ in 0..30 {
+        let path = selector.select(0, 9, || Ok(paths.clone())).unwrap();
+        *counts.entry(path[1]).or_insert(0) += 1;
+    }
+    assert_eq!(counts.get(&2), Some(&20));
+    assert_eq!(counts.get(&3), Some(&10));
+    assert_eq!(counts.get(&4), None);
+
+    // the same epoch reuses the candidates, a new one recomputes them
+    assert!(selector.select(0, 9, || unreachable!()).is_ok());
+    let path = selector
+        .select(1, 9, || Ok(vec![(vec![1, 4, 9], 0)]))
+        .unwrap();
+    assert_eq!(path, vec![1, 4, 9]);
+    assert!(selector.select(2, 9, || Ok(Vec::new())).is_err());
+
+    // the routes computed again keep their credit, a new epoch at every packet
+    // still spreads the traffic
+    let paths = vec![(vec![1, 2, 9], 0), (vec![1, 3, 9], 0)];
+    let picks: Vec<NodeId> = (3..7)
+        .map(|epoch| selector.select(epoch, 9, || Ok(paths.clone())).unwrap()[1])
+        .collect();
+    assert_eq!(picks.iter().filter(|hop| **hop == 2).count(), 2);
+}
+
+#[test]
+fn test_cost_proportional() {
+    let mut selector = PathSelector::new(SelectionStrategy::CostProportional);
+    selector.rng = StdRng::seed_from_u64(7);
+    let paths = vec![(vec![1, 2, 9], 0), (vec![1, 3, 9], 1)];
+
+    let mut counts = HashMap::new();
+    for _ in 0..3000 {
+        let path = selector.select(0, 9, || Ok(paths.clone())).unwrap();
+        *counts.entry(path[1]).or_insert(0) += 1;
+    }
+    let cheap = counts[&2];
+    let expensive = counts[&3];
+    assert_eq!(cheap + expensive, 3000);
+    // expected 2000 and 1000
+    assert!((1850..2150).contains(&cheap), "{cheap}");
+}
+
+#[test]
+fn test_ucb() {
+    let mut selector = PathSelector::new(SelectionStrategy::Ucb);
+    // every route is an arm, even the expensive ones
+    let paths = vec![(vec![1, 2, 9], 0), (vec![1, 3, 9], 4), (vec![1, 4, 9], 9)];
+    let ack_probability = HashMap::from([(2, 0.0), (3, 0.9), (4, 0.5)]);
+    let mut rng = StdRng::seed_from_u64(3);
+
+    let mut counts = HashMap::new();
+    for round in 0..600 {
+        // the candidates are refreshed but the outcomes are not forgotten
+        let path = selector
+            .select(round / 50, 9, || Ok(paths.clone()))
+            .unwrap();
+        *counts.entry(path[1]).or_insert(0) += 1;
+        let outcome = if rng.random_bool(ack_probability[&path[1]]) {
+            Outcome::Acked
+        } else {
+            Outcome::Nacked
+        };
+        selector.report(&path, outcome);
+    }
+    assert!(counts[&3] > 400, "{counts:?}");
+    assert!(counts[&2] < counts[&4], "{counts:?}");
+}
+
+#[test]
+fn test_ucb_eviction() {
+    let mut selector = PathSelector::new(SelectionStrategy::Ucb);
+    let paths = vec![(vec![1, 2, 9], 0), (vec![1, 3, 9], 0)];
+    for _ in 0..2 {
+        let path = selector.select(0, 9, || Ok(paths.clone())).unwrap();
+        selector.report(&path, Outcome::Acked);
+    }
+    // routes never selected are not tracked
+    selector.report(&[1, 4, 9], Outcome::Nacked);
+    assert_eq!(selector.arms[&9].len(), 2);
+
+    // 3 is gone: its outcomes are forgotten, the ones of 2 are kept
+    let _ = selector.select(1, 9, || Ok(vec![(vec![1, 2, 9], 0)]));
+    assert_eq!(
+        selector.arms[&9].keys().collect::<Vec<_>>(),
+        vec![&vec![1, 2, 9]]
+    );
+}
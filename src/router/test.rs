@@ -0,0 +1,184 @@
+	/*   <source_routing - lib for Rustastic client and server>     Copyright (C) <2025>  <Giulio Bosio>      This program is free software: you can redistribute it and/or modify     it under the terms of the GNU General Public License as published by     the Free Software Foundation, either version 3 of the License, or     (at your option) any later version.      This program is distributed in the hope that it will be useful,     but WITHOUT ANY WARRANTY; without even the implied warranty of     MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the     GNU General Public License for more details.      You should have received a copy of the GNU General Public License     along with this program.  If not, see <https://www.gnu.org/licenses/>.  	*/
+
+use super::*;
+use crate::error::PathTraceError;
+use std::sync::Arc;
+use wg_2024::{
+    network::SourceRoutingHeader,
+    packet::{FloodResponse, Nack},
+};
+
+fn response(flood_id: u64, path_trace: &[NodeId]) -> FloodResponse {
+    FloodResponse {
+        flood_id,
+        path_trace: path_trace
+            .iter()
+            .map(|id| {
+                let node_type = match id {
+                    1 => NodeType::Client,
+                    8 | 9 => NodeType::Server,
+                    _ => NodeType::Drone,
+                };
+                (*id, node_type)
+            })
+            .collect(),
+    }
+}
+
+fn nack(hops: Vec<NodeId>, nack_type: NackType) -> Packet {
+    Packet {
+        routing_header: SourceRoutingHeader::with_first_hop(hops),
+        session_id: 0,
+        pack_type: PacketType::Nack(Nack {
+            fragment_index: 0,
+            nack_type,
+        }),
+    }
+}
+
+#[test]
+fn test_selection_with_acks() {
+    let mut router = Router::new(1, NodeType::Client);
+    let _ = router.get_flood_requests(1);
+    for trace in [&[1, 2, 9][..], &[1, 3, 9]] {
+        router.handle_flood_response(&response(0, trace));
+    }
+    // the routes come from the pending round, as for the other getters
+    assert_eq!(router.get_multiple_source_routing_headers(9).len(), 2);
+
+    // the acks do not change the routes of the default metric, and the packets
+    // keep being spread over both of them
+    let epoch = router.topology_epoch();
+    let mut next_hops = Vec::new();
+    for _ in 0..4 {
+        let header = router.next_source_routing_header(9).unwrap();
+        router.packet_sent(&header);
+        router.packet_acked(&header);
+        next_hops.push(header.hops[1]);
+    }
+    assert_eq!(router.topology_epoch(), epoch);
+    assert_eq!(next_hops.iter().filter(|hop| **hop == 2).count(), 2);
+}
+
+#[test]
+fn test_handle_nack() {
+    let mut router = Router::new(1, NodeType::Client);
+    let _ = router.get_flood_requests(1);
+    for trace in [&[1, 2, 3, 9][..], &[1, 4, 3, 9], &[1, 2, 5, 9], &[1, 4, 8]] {
+        router.handle_flood_response(&response(0, trace));
+    }
+
+    // 2 has no link with 3
+    router.handle_nack(&nack(vec![2, 1], NackType::ErrorInRouting(3)));
+    assert!(router.link_confidence(2, 3).is_none());
+    // and the late responses do not bring it back
+    router.handle_flood_response(&response(0, &[1, 2, 3, 9]));
+    assert!(router.link_confidence(2, 3).is_none());
+
+    // 8 is a drone
+    router.handle_nack(&nack(vec![8, 4, 1], NackType::DestinationIsDrone));
+    assert_eq!(router.node_type(8), Some(NodeType::Drone));
+    assert_eq!(router.get_server_list(), HashSet::from([9]));
+    // the pending responses of the floods requested before do not change it back
+    router.handle_flood_response(&response(0, &[1, 4, 8]));
+    assert_eq!(router.node_type(8), Some(NodeType::Drone));
+
+    // both routes to 9 are used until 2 turns out to lead to 3 instead of 5
+    let mut next_hops = HashSet::new();
+    for _ in 0..4 {
+        next_hops.insert(router.next_source_routing_header(9).unwrap().hops[1]);
+    }
+    assert_eq!(next_hops, HashSet::from([2, 4]));
+    router.handle_nack(&nack(vec![3, 2, 1], NackType::UnexpectedRecipient(3)));
+    // the acks and the drops change the costs, not the structure: 2 stays stale
+    // even once the drops on 4 make it the cheapest way
+    let epoch = router.topology_epoch();
+    for _ in 0..4 {
+        let header = router.next_source_routing_header(9).unwrap();
+        assert_eq!(header.hops, vec![1, 4, 3, 9]);
+        router.packet_sent(&header);
+        router.packet_acked(&header);
+        router.dropped_fragment(4);
+    }
+    assert_ne!(router.topology_epoch(), epoch);
+
+    router.handle_nack(&nack(vec![4, 1], NackType::Dropped));
+    assert!(router.estimated_pdr(4).is_some());
+    assert_eq!(
+        router.get_source_routing_header(9).unwrap().hops,
+        vec![1, 2, 5, 9]
+    );
+}
+
+#[test]
+fn test_flood_guard() {
+    let clock = Arc::new(ManualClock::new());
+    let mut router = Router::new(1, NodeType::Client);
+    router.set_clock(clock.clone());
+    let _ = router.get_flood_requests(1);
+
+    router.handle_flood_response(&response(0, &[1, 2, 9]));
+    // never requested
+    router.handle_flood_response(&response(7, &[1, 3, 9]));
+    // started by someone else
+    router.handle_flood_response(&response(0, &[4, 3, 9]));
+    assert!(router.get_source_routing_header(9).is_ok());
+    assert_eq!(router.get_server_list().len(), 1);
+    assert!(router.link_confidence(1, 3).is_none());
+
+    // late responses are still learned
+    clock.advance(FLOOD_ROUND_TIMEOUT);
+    router.handle_flood_response(&response(0, &[1, 5, 9]));
+    assert!(router.link_confidence(1, 5).is_some());
+    assert_eq!(router.flood_round(0).unwrap().responses, 2);
+    // but not in strict mode
+    router.set_strict_flood_responses(true);
+    router.handle_flood_response(&response(0, &[1, 6, 9]));
+    assert!(router.link_confidence(1, 6).is_none());
+    assert_eq!(router.flood_round(0).unwrap().responses, 2);
+
+    // malformed traces are repaired, or rejected
+    let _ = router.get_flood_requests(1);
+    router.handle_flood_response(&response(1, &[1, 7, 7, 9, 8]));
+    clock.advance(FLOOD_ROUND_TIMEOUT);
+    assert!(router.link_confidence(7, 9).is_some());
+    assert!(router.link_confidence(9, 8).is_none());
+    router.set_path_trace_repair(false);
+    router.handle_flood_response(&response(1, &[1, 7, 9, 8]));
+
+    // the responses of a rolled back round are admitted, but neither learned nor counted
+    let _ = router.get_flood_requests(1);
+    assert!(router.rollback_generation().is_some());
+    router.handle_flood_response(&response(2, &[1, 3, 9]));
+    assert!(router.link_confidence(1, 3).is_none());
+
+    assert_eq!(
+        router.flood_response_stats(),
+        FloodResponseStats {
+            accepted: 3,
+            unknown_flood: 1,
+            wrong_initiator: 1,
+            invalid_path_trace: 1,
+            stale: 2,
+        }
+    );
+    assert_eq!(
+        router
+            .quarantined_flood_responses()
+            .into_iter()
+            .map(|(response, rejection)| (response.flood_id, rejection))
+            .collect::<Vec<_>>(),
+        vec![
+            (7, FloodRejection::UnknownFlood),
+            (0, FloodRejection::WrongInitiator),
+            (0, FloodRejection::Stale),
+            (
+                1,
+                FloodRejection::InvalidPathTrace(PathTraceError::LeafForwarded {
+                    id: 9,
+                    node_type: NodeType::Server
+                })
+            )
+        ]
+    );
+}